@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+use gtk::gio;
+use gtk::prelude::*;
+
+/// Parses text like `code-url-handler = Code` into the window class and the
+/// app it is merged into.
+pub fn parse(text: &str) -> Option<(String, String)> {
+    let (class, app) = text.split_once('=')?;
+    let (class, app) = (class.trim(), app.trim());
+    if class.is_empty() || app.is_empty() || class == app {
+        return None;
+    }
+    Some((class.to_string(), app.to_string()))
+}
+
+/// Reads the `app-aliases` setting.
+pub fn load(settings: &gio::Settings) -> HashMap<String, String> {
+    settings.get("app-aliases")
+}
+
+pub fn store(settings: &gio::Settings, aliases: &HashMap<String, String>) {
+    settings
+        .set("app-aliases", aliases)
+        .expect("failed to set app aliases");
+}
@@ -0,0 +1,127 @@
+use std::{collections::HashMap, time::Duration};
+
+use gtk::gio;
+use gtk::prelude::*;
+
+use crate::duration_format::DurationFormat;
+
+/// Whether a daily target is a maximum or a minimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppGoalKind {
+    /// At most this much time per day.
+    Limit,
+    /// At least this much time per day.
+    Goal,
+}
+
+impl AppGoalKind {
+    pub fn from_nick(nick: &str) -> Option<Self> {
+        match nick {
+            "limit" => Some(AppGoalKind::Limit),
+            "goal" => Some(AppGoalKind::Goal),
+            _ => None,
+        }
+    }
+
+    /// Nick used by the `app-goals` setting.
+    pub fn nick(self) -> &'static str {
+        match self {
+            AppGoalKind::Limit => "limit",
+            AppGoalKind::Goal => "goal",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            AppGoalKind::Limit => "≤",
+            AppGoalKind::Goal => "≥",
+        }
+    }
+}
+
+/// Daily limit or goal of an app, see the `app-goals` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppGoal {
+    pub kind: AppGoalKind,
+    pub daily: Duration,
+}
+
+impl AppGoal {
+    /// Parses text like `Firefox <= 2h` or `Code ≥ 4h 30m` into the app and
+    /// its goal.
+    pub fn parse(text: &str) -> Option<(String, AppGoal)> {
+        let operators = [
+            ("<=", AppGoalKind::Limit),
+            ("≤", AppGoalKind::Limit),
+            (">=", AppGoalKind::Goal),
+            ("≥", AppGoalKind::Goal),
+        ];
+        let (index, operator, kind) = operators
+            .into_iter()
+            .filter_map(|(operator, kind)| Some((text.find(operator)?, operator, kind)))
+            .min_by_key(|(index, _, _)| *index)?;
+
+        let app = text[..index].trim();
+        let daily = humantime::parse_duration(text[index + operator.len()..].trim()).ok()?;
+        if app.is_empty() || daily.is_zero() {
+            return None;
+        }
+        Some((app.to_string(), AppGoal { kind, daily }))
+    }
+
+    /// Target over a range of `days` days.
+    pub fn target(self, days: u32) -> Duration {
+        self.daily * days.max(1)
+    }
+
+    /// Fraction of the target reached by `duration`, may be above 1.
+    pub fn progress(self, duration: Duration, days: u32) -> f64 {
+        duration.as_secs_f64() / self.target(days).as_secs_f64()
+    }
+
+    /// Whether `duration` went over a limit.
+    pub fn is_exceeded(self, duration: Duration, days: u32) -> bool {
+        self.kind == AppGoalKind::Limit && duration > self.target(days)
+    }
+
+    /// Whether `duration` reached a goal.
+    pub fn is_met(self, duration: Duration, days: u32) -> bool {
+        self.kind == AppGoalKind::Goal && duration >= self.target(days)
+    }
+
+    /// Short description like `≤ 2h`, with the daily target shown in
+    /// `duration_format`.
+    pub fn describe(self, duration_format: DurationFormat) -> String {
+        format!(
+            "{} {}",
+            self.kind.symbol(),
+            duration_format.format(self.daily)
+        )
+    }
+}
+
+/// Reads the `app-goals` setting, skipping malformed entries.
+pub fn load(settings: &gio::Settings) -> HashMap<String, AppGoal> {
+    settings
+        .get::<HashMap<String, (String, u32)>>("app-goals")
+        .into_iter()
+        .filter_map(|(app, (kind, seconds))| {
+            let kind = AppGoalKind::from_nick(&kind)?;
+            let daily = Duration::from_secs(seconds.into());
+            Some((app, AppGoal { kind, daily }))
+        })
+        .collect()
+}
+
+pub fn store(settings: &gio::Settings, goals: &HashMap<String, AppGoal>) {
+    let goals: HashMap<String, (String, u32)> = goals
+        .iter()
+        .map(|(app, goal)| {
+            let seconds = goal.daily.as_secs().min(u32::MAX.into()) as u32;
+            (app.clone(), (goal.kind.nick().to_string(), seconds))
+        })
+        .collect();
+    settings
+        .set("app-goals", goals)
+        .expect("failed to set app goals");
+}
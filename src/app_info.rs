@@ -0,0 +1,68 @@
+use std::{cell::RefCell, collections::HashMap, path::Path};
+
+use gtk::gio;
+use gtk::prelude::*;
+
+/// How an app is presented instead of its raw window class.
+#[derive(Debug, Clone)]
+pub struct AppDisplayInfo {
+    pub name: String,
+    pub icon: Option<gio::Icon>,
+}
+
+thread_local! {
+    static CACHE: RefCell<HashMap<String, AppDisplayInfo>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the localized name and icon of the desktop entry matching `class`,
+/// falling back to the class itself.
+pub fn display_info(class: &str) -> AppDisplayInfo {
+    CACHE.with(|cache| {
+        cache
+            .borrow_mut()
+            .entry(class.to_string())
+            .or_insert_with(|| match find_desktop_app_info(class) {
+                Some(app_info) => AppDisplayInfo {
+                    name: app_info.display_name().to_string(),
+                    icon: app_info.icon(),
+                },
+                None => AppDisplayInfo {
+                    name: class.to_string(),
+                    icon: None,
+                },
+            })
+            .clone()
+    })
+}
+
+/// Matches `class` against, in order, the desktop file id, the
+/// `StartupWMClass` key and the executable of every installed app.
+fn find_desktop_app_info(class: &str) -> Option<gio::DesktopAppInfo> {
+    for id in [class.to_string(), class.to_lowercase()] {
+        if let Some(app_info) = gio::DesktopAppInfo::new(&format!("{id}.desktop")) {
+            return Some(app_info);
+        }
+    }
+
+    let app_infos: Vec<gio::DesktopAppInfo> = gio::AppInfo::all()
+        .into_iter()
+        .filter_map(|app_info| app_info.downcast().ok())
+        .collect();
+
+    app_infos
+        .iter()
+        .find(|app_info| {
+            app_info
+                .startup_wm_class()
+                .is_some_and(|wm_class| wm_class.eq_ignore_ascii_case(class))
+        })
+        .or_else(|| {
+            app_infos.iter().find(|app_info| {
+                Path::new(&app_info.executable())
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.eq_ignore_ascii_case(class))
+            })
+        })
+        .cloned()
+}
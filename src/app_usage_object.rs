@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    #[derive(Debug, Default, glib::Properties)]
+    #[properties(wrapper_type = super::AppUsageObject)]
+    pub struct AppUsageObject {
+        /// Window class of the app, or name of the category.
+        #[property(get, set)]
+        title: RefCell<String>,
+        #[property(get, set)]
+        display_name: RefCell<String>,
+        #[property(get, set, nullable)]
+        icon: RefCell<Option<gio::Icon>>,
+        #[property(get, set)]
+        seconds: Cell<f64>,
+        /// Seconds used in the period of the same length just before.
+        #[property(get, set)]
+        previous_seconds: Cell<f64>,
+        /// Share of the total usage of the range, from 0 to 100.
+        #[property(get, set)]
+        percentage: Cell<f64>,
+        #[property(get, set)]
+        daily_average_seconds: Cell<f64>,
+        #[property(get, set)]
+        duration_label: RefCell<String>,
+        #[property(get, set)]
+        percentage_label: RefCell<String>,
+        #[property(get, set)]
+        daily_average_label: RefCell<String>,
+        /// Change from the previous period, like `+15m ▲`.
+        #[property(get, set)]
+        change_label: RefCell<String>,
+        /// Fraction of the daily limit or goal reached, up to 1.
+        #[property(get, set)]
+        goal_progress: Cell<f64>,
+        /// Describes the limit or goal, `None` without one.
+        #[property(get, set, nullable)]
+        goal_tooltip: RefCell<Option<String>>,
+        #[property(get, set)]
+        exceeded: Cell<bool>,
+        #[property(get, set)]
+        goal_met: Cell<bool>,
+
+        /// Apps of a category, `None` for an app.
+        pub(super) children: RefCell<Option<gio::ListStore>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AppUsageObject {
+        const NAME: &'static str = "AppUsageObject";
+        type Type = super::AppUsageObject;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for AppUsageObject {}
+}
+
+glib::wrapper! {
+    /// Usage of an app or a category in the usage list.
+    pub struct AppUsageObject(ObjectSubclass<imp::AppUsageObject>);
+}
+
+impl AppUsageObject {
+    pub fn new(title: &str, display_name: &str, duration: Duration) -> Self {
+        glib::Object::builder()
+            .property("title", title)
+            .property("display-name", display_name)
+            .property("seconds", duration.as_secs_f64())
+            .build()
+    }
+
+    /// Creates the object of a category holding `apps`.
+    pub fn new_category(title: &str, duration: Duration, apps: &[AppUsageObject]) -> Self {
+        let object = Self::new(title, title, duration);
+        let children = gio::ListStore::new::<AppUsageObject>();
+        children.extend_from_slice(apps);
+        object.imp().children.replace(Some(children));
+        object
+    }
+
+    pub fn is_category(&self) -> bool {
+        self.imp().children.borrow().is_some()
+    }
+
+    pub fn children(&self) -> Option<gio::ListStore> {
+        self.imp().children.borrow().clone()
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.seconds())
+    }
+
+    pub fn previous_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.previous_seconds())
+    }
+
+    /// Whether the title or display name, or those of any of the apps of a
+    /// category, contains `text`, ignoring case.
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.title().to_lowercase().contains(&text)
+            || self.display_name().to_lowercase().contains(&text)
+            || self.children().is_some_and(|children| {
+                children
+                    .iter::<AppUsageObject>()
+                    .flatten()
+                    .any(|child| child.matches(&text))
+            })
+    }
+
+    /// Copies the usage of `other`, an object with the same title, so the
+    /// rows showing this one update in place.
+    fn update_from(&self, other: &AppUsageObject) {
+        self.set_display_name(other.display_name());
+        self.set_icon(other.icon());
+        self.set_seconds(other.seconds());
+        self.set_previous_seconds(other.previous_seconds());
+        self.set_percentage(other.percentage());
+        self.set_daily_average_seconds(other.daily_average_seconds());
+        self.set_duration_label(other.duration_label());
+        self.set_percentage_label(other.percentage_label());
+        self.set_daily_average_label(other.daily_average_label());
+        self.set_change_label(other.change_label());
+        self.set_goal_progress(other.goal_progress());
+        self.set_goal_tooltip(other.goal_tooltip());
+        self.set_exceeded(other.exceeded());
+        self.set_goal_met(other.goal_met());
+
+        if let (Some(children), Some(other_children)) = (self.children(), other.children()) {
+            let other_children: Vec<AppUsageObject> = other_children.iter().flatten().collect();
+            sync_store(&children, &other_children);
+        }
+    }
+}
+
+/// Makes `store` hold `objects`, updating the objects it already has with
+/// the same title instead of replacing them, so selection and expanded
+/// categories survive a refresh.
+pub fn sync_store(store: &gio::ListStore, objects: &[AppUsageObject]) {
+    for position in (0..store.n_items()).rev() {
+        let Some(existing) = store.item(position).and_downcast::<AppUsageObject>() else {
+            continue;
+        };
+        let title = existing.title();
+        if !objects.iter().any(|object| object.title() == title) {
+            store.remove(position);
+        }
+    }
+
+    for object in objects {
+        let title = object.title();
+        let existing = store
+            .iter::<AppUsageObject>()
+            .flatten()
+            .find(|existing| existing.title() == title);
+        match existing {
+            Some(existing) => existing.update_from(object),
+            None => store.append(object),
+        }
+    }
+}
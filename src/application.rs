@@ -21,15 +21,80 @@
 use adw::subclass::prelude::*;
 use gtk::prelude::*;
 use gtk::{gio, glib};
+use hyprland_app_timer::SqliteDB;
+use tokio::sync::OnceCell;
+
+use std::{future::Future, sync::Arc};
 
 use crate::config::VERSION;
+use crate::preferences::HyprlandAppTimerGuiPreferencesWindow;
 use crate::HyprlandAppTimerGuiWindow;
 
 mod imp {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        time::{Duration, Instant},
+    };
+
+    use chrono::{Local, NaiveDate};
+    use gettextrs::gettext;
+    use gtk::glib::{Receiver, Sender};
+    use hyprland_app_timer::AppUsage;
+    use tokio::runtime::Runtime;
+
+    use crate::app_goal::{self, AppGoal, AppGoalKind};
+    use crate::app_info;
+    use crate::config::APP_ID;
+    use crate::duration_format::DurationFormat;
+    use crate::usage::{self, UsageFilter};
+
     use super::*;
 
-    #[derive(Debug, Default)]
-    pub struct HyprlandAppTimerGuiApplication {}
+    /// Seconds between checks of the daily limits.
+    const LIMIT_CHECK_INTERVAL: u32 = 60;
+    const SNOOZE_DURATION: Duration = Duration::from_secs(15 * 60);
+
+    #[derive(Debug)]
+    pub struct HyprlandAppTimerGuiApplication {
+        settings: gio::Settings,
+        sender: Sender<(NaiveDate, Vec<AppUsage>)>,
+        receiver: RefCell<Option<Receiver<(NaiveDate, Vec<AppUsage>)>>>,
+        /// Shared by every window so work outlives them.
+        pub(super) rt: Runtime,
+        /// Opened on first use so a missing database can be retried.
+        pub(super) db: Arc<OnceCell<SqliteDB>>,
+        /// Set by `--background` to start without a window.
+        background: Cell<bool>,
+        /// Keeps the process alive without windows in background mode.
+        hold_guard: RefCell<Option<gio::ApplicationHoldGuard>>,
+        /// Day on which each app was last notified about its limit.
+        notified: RefCell<HashMap<String, NaiveDate>>,
+        /// When to notify again about the apps snoozed from a notification.
+        snoozed: RefCell<HashMap<String, Instant>>,
+    }
+
+    impl Default for HyprlandAppTimerGuiApplication {
+        fn default() -> Self {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .build()
+                .expect("failed to buid tokio runtime");
+            let (sender, receiver) = glib::MainContext::channel(glib::Priority::DEFAULT);
+            Self {
+                settings: gio::Settings::new(APP_ID),
+                sender,
+                receiver: RefCell::new(Some(receiver)),
+                rt,
+                db: Default::default(),
+                background: Default::default(),
+                hold_guard: Default::default(),
+                notified: Default::default(),
+                snoozed: Default::default(),
+            }
+        }
+    }
 
     #[glib::object_subclass]
     impl ObjectSubclass for HyprlandAppTimerGuiApplication {
@@ -44,15 +109,69 @@ mod imp {
             let obj = self.obj();
             obj.setup_gactions();
             obj.set_accels_for_action("app.quit", &["<primary>q"]);
+            obj.set_accels_for_action("win.export", &["<primary>e"]);
+            obj.set_accels_for_action("win.search", &["<primary>f"]);
+            obj.set_accels_for_action("app.preferences", &["<primary>comma"]);
+            obj.add_main_option(
+                "background",
+                glib::Char::from(b'b'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::None,
+                &gettext("Keep running without a window to check the daily limits"),
+                None,
+            );
         }
     }
 
     impl ApplicationImpl for HyprlandAppTimerGuiApplication {
+        fn handle_local_options(&self, options: &glib::VariantDict) -> glib::ExitCode {
+            self.background.set(options.contains("background"));
+            self.parent_handle_local_options(options)
+        }
+
+        fn startup(&self) {
+            self.parent_startup();
+
+            // with --gapplication-service the application is never activated
+            if self
+                .obj()
+                .flags()
+                .contains(gio::ApplicationFlags::IS_SERVICE)
+            {
+                self.hold();
+            }
+
+            let receiver = self.receiver.take().unwrap();
+            receiver.attach(
+                None,
+                glib::clone!(@weak self as this => @default-return glib::ControlFlow::Break,
+                    move |(day, apps_usage)| {
+                        this.notify_exceeded_limits(day, &apps_usage);
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+
+            glib::timeout_add_seconds_local(
+                LIMIT_CHECK_INTERVAL,
+                glib::clone!(@weak self as this => @default-return glib::ControlFlow::Break, move || {
+                    this.check_limits();
+                    glib::ControlFlow::Continue
+                }),
+            );
+        }
+
         // We connect to the activate callback to create a window when the application
         // has been launched. Additionally, this callback notifies us when the user
         // tries to launch a "second instance" of the application. When they try
         // to do that, we'll just present any existing window.
         fn activate(&self) {
+            // only the first activation of a --background launch stays hidden
+            if self.background.replace(false) {
+                self.hold();
+                return;
+            }
+
             let application = self.obj();
             // Get the current window or create one if necessary
             let window = if let Some(window) = application.active_window() {
@@ -69,6 +188,123 @@ mod imp {
 
     impl GtkApplicationImpl for HyprlandAppTimerGuiApplication {}
     impl AdwApplicationImpl for HyprlandAppTimerGuiApplication {}
+
+    impl HyprlandAppTimerGuiApplication {
+        fn hold(&self) {
+            if self.hold_guard.borrow().is_none() {
+                self.hold_guard.replace(Some(self.obj().hold()));
+            }
+        }
+
+        /// Loads today's usage in the background if any limit is set.
+        fn check_limits(&self) {
+            let has_limits = app_goal::load(&self.settings)
+                .values()
+                .any(|goal| goal.kind == AppGoalKind::Limit);
+            if !has_limits {
+                return;
+            }
+
+            let db = self.db.clone();
+            let sender = self.sender.clone();
+            // hidden apps don't notify even while shown
+            let filter = UsageFilter {
+                show_hidden: false,
+                ..UsageFilter::from_settings(&self.settings)
+            };
+            let today = Local::now().date_naive();
+            self.rt.spawn(async move {
+                if let Err(err) = usage::save().await {
+                    eprintln!("Error: failed to save usage: {err}");
+                }
+                let result = async {
+                    let db = db
+                        .get_or_try_init(SqliteDB::new)
+                        .await
+                        .map_err(|err| err.to_string())?;
+                    usage::fetch_day(db, today)
+                        .await
+                        .map(|apps_usage| filter.apply(apps_usage))
+                }
+                .await;
+                match result {
+                    Ok(apps_usage) => sender
+                        .send((today, apps_usage))
+                        .expect("failed to send today's usage"),
+                    Err(err) => eprintln!("Error: failed to check limits: {err}"),
+                }
+            });
+        }
+
+        /// Notifies once a day, or again after a snooze, about every app over
+        /// its limit on `day`.
+        fn notify_exceeded_limits(&self, day: NaiveDate, apps_usage: &[AppUsage]) {
+            let goals = app_goal::load(&self.settings);
+            let now = Instant::now();
+
+            for app_usage in apps_usage {
+                let Some(goal) = goals.get(&app_usage.app) else {
+                    continue;
+                };
+                if !goal.is_exceeded(app_usage.duration, 1) {
+                    continue;
+                }
+
+                let mut snoozed = self.snoozed.borrow_mut();
+                let mut notified = self.notified.borrow_mut();
+                match snoozed.get(&app_usage.app) {
+                    Some(until) if now < *until => continue,
+                    Some(_) => {
+                        snoozed.remove(&app_usage.app);
+                    }
+                    None if notified.get(&app_usage.app) == Some(&day) => continue,
+                    None => {}
+                }
+                notified.insert(app_usage.app.clone(), day);
+                self.send_limit_notification(&app_usage.app, app_usage.duration, *goal);
+            }
+        }
+
+        fn send_limit_notification(&self, app: &str, duration: Duration, goal: AppGoal) {
+            let duration_format =
+                DurationFormat::from_nick(&self.settings.string("duration-format"));
+            let name = app_info::display_info(app).name;
+            let notification =
+                gio::Notification::new(&gettext!("Daily limit reached for {}", name));
+            notification.set_body(Some(&gettext!(
+                "You have used {} for {} today, over your limit of {}.",
+                name,
+                duration_format.format(duration),
+                duration_format.format(goal.daily)
+            )));
+            let target = app.to_variant();
+            notification.set_default_action_and_target_value("app.show-app", Some(&target));
+            notification.add_button_with_target_value(
+                &gettext("Snooze 15 min"),
+                "app.snooze-limit",
+                Some(&target),
+            );
+            notification.add_button_with_target_value(
+                &gettext("Open"),
+                "app.show-app",
+                Some(&target),
+            );
+            self.obj()
+                .send_notification(Some(&limit_notification_id(app)), &notification);
+        }
+
+        pub(super) fn snooze_limit(&self, app: &str) {
+            self.snoozed
+                .borrow_mut()
+                .insert(app.to_string(), Instant::now() + SNOOZE_DURATION);
+            self.obj()
+                .withdraw_notification(&limit_notification_id(app));
+        }
+    }
+
+    fn limit_notification_id(app: &str) -> String {
+        format!("limit-{app}")
+    }
 }
 
 glib::wrapper! {
@@ -92,13 +328,72 @@ impl HyprlandAppTimerGuiApplication {
         let about_action = gio::ActionEntry::builder("about")
             .activate(move |app: &Self, _, _| app.show_about())
             .build();
-        self.add_action_entries([quit_action, about_action]);
+        let preferences_action = gio::ActionEntry::builder("preferences")
+            .activate(move |app: &Self, _, _| app.show_preferences())
+            .build();
+        let show_app_action = gio::ActionEntry::builder("show-app")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(move |app: &Self, _, parameter| {
+                if let Some(app_name) = parameter.and_then(|parameter| parameter.str()) {
+                    app.show_app(app_name);
+                }
+            })
+            .build();
+        let snooze_limit_action = gio::ActionEntry::builder("snooze-limit")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(move |app: &Self, _, parameter| {
+                if let Some(app_name) = parameter.and_then(|parameter| parameter.str()) {
+                    app.imp().snooze_limit(app_name);
+                }
+            })
+            .build();
+        self.add_action_entries([
+            quit_action,
+            about_action,
+            preferences_action,
+            show_app_action,
+            snooze_limit_action,
+        ]);
+    }
+
+    pub fn db(&self) -> Arc<OnceCell<SqliteDB>> {
+        self.imp().db.clone()
+    }
+
+    /// Runs `future` on the runtime shared by every window.
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.imp().rt.spawn(future);
+    }
+
+    /// Presents the window with the details of `app_name`.
+    fn show_app(&self, app_name: &str) {
+        self.activate();
+        if let Some(window) = self
+            .active_window()
+            .and_downcast::<HyprlandAppTimerGuiWindow>()
+        {
+            window.show_app(app_name);
+        }
+    }
+
+    fn show_preferences(&self) {
+        let preferences = match self.active_window() {
+            Some(window) => HyprlandAppTimerGuiPreferencesWindow::new(Some(&window)),
+            // in background mode there may be no window to attach to
+            None => {
+                let preferences = HyprlandAppTimerGuiPreferencesWindow::new(None);
+                preferences.set_application(Some(self));
+                preferences
+            }
+        };
+        preferences.present();
     }
 
     fn show_about(&self) {
-        let window = self.active_window().unwrap();
         let about = adw::AboutWindow::builder()
-            .transient_for(&window)
             .application_name("hyprland-app-timer-gui")
             .application_icon("io.github.zd4y.HyprlandAppTimer")
             .developer_name("zd4y")
@@ -106,6 +401,8 @@ impl HyprlandAppTimerGuiApplication {
             .developers(vec!["zd4y"])
             .copyright("© 2023 zd4y")
             .build();
+        // in background mode there may be no window to attach to
+        about.set_transient_for(self.active_window().as_ref());
 
         about.present();
     }
@@ -0,0 +1,231 @@
+use adw::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{cairo, glib};
+
+use std::time::Duration;
+
+use crate::duration_format::DurationFormat;
+use crate::pie_chart::{item_color, Palette};
+
+/// One app's share of a bar.
+#[derive(Debug, Clone)]
+pub struct BarChartSegment {
+    pub title: String,
+    pub weight: f64,
+    /// Index passed to `item_color`, so the app keeps its color in every chart.
+    pub color_index: usize,
+}
+
+/// A single stacked bar, usually one day.
+#[derive(Debug, Clone)]
+pub struct BarChartBar {
+    pub title: String,
+    pub segments: Vec<BarChartSegment>,
+}
+
+impl BarChartBar {
+    pub fn total(&self) -> f64 {
+        self.segments.iter().map(|segment| segment.weight).sum()
+    }
+}
+
+mod imp {
+    const MARGIN: f64 = 12.0;
+    const LABEL_HEIGHT: f64 = 24.0;
+    const BAR_SPACING: f64 = 0.25;
+
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    #[derive(Debug, Default, glib::Properties)]
+    #[properties(wrapper_type = super::BarChart)]
+    pub struct BarChart {
+        pub(super) bars: RefCell<Vec<BarChartBar>>,
+        highlighted_segment: Cell<Option<(usize, usize)>>,
+        width: Cell<f64>,
+        height: Cell<f64>,
+        pub(super) duration_format: Cell<DurationFormat>,
+
+        #[property(get, set = Self::set_palette, builder(Palette::default()))]
+        palette: Cell<Palette>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BarChart {
+        const NAME: &'static str = "BarChart";
+        type Type = super::BarChart;
+        type ParentType = gtk::DrawingArea;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for BarChart {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_draw_func(glib::clone!(@weak self as widget => move |_, cr, w, h| {
+                widget.draw_chart(cr, w, h);
+            }));
+
+            let motion_controller = gtk::EventControllerMotion::new();
+            motion_controller.connect_motion(glib::clone!(@weak self as widget => move |_, x, y| {
+                widget.highlight_segment_at_point(x, y);
+            }));
+            motion_controller.connect_leave(glib::clone!(@weak self as widget => move |_| {
+                widget.set_highlighted_segment(None);
+            }));
+            obj.add_controller(motion_controller);
+        }
+    }
+    impl WidgetImpl for BarChart {}
+    impl DrawingAreaImpl for BarChart {}
+
+    impl BarChart {
+        fn set_palette(&self, palette: Palette) {
+            self.palette.set(palette);
+            self.obj().queue_draw();
+        }
+
+        fn draw_chart(&self, context: &cairo::Context, width: i32, height: i32) {
+            let obj = self.obj();
+            self.width.set(width.into());
+            self.height.set(height.into());
+
+            let bars = self.bars.borrow();
+            let highlighted_segment = self.highlighted_segment.get();
+
+            for (bar_index, bar) in bars.iter().enumerate() {
+                let (x, bar_width) = self.bar_bounds(bar_index);
+
+                let layout = obj.create_pango_layout(Some(&bar.title));
+                let (_, logical) = layout.pixel_extents();
+                context.set_source_rgba(0.5, 0.5, 0.5, 1.0);
+                context.move_to(
+                    x + (bar_width - logical.width() as f64) / 2.0,
+                    self.height.get() - LABEL_HEIGHT + 4.0,
+                );
+                pangocairo::functions::show_layout(context, &layout);
+
+                for (segment_index, (y, segment_height)) in
+                    self.segment_bounds(bar).into_iter().enumerate()
+                {
+                    let segment = &bar.segments[segment_index];
+                    let highlighted = highlighted_segment == Some((bar_index, segment_index));
+                    GdkCairoContextExt::set_source_rgba(
+                        context,
+                        &item_color(self.palette.get(), segment.color_index, highlighted),
+                    );
+                    context.rectangle(x, y, bar_width, segment_height);
+                    context.fill().expect("failed to fill");
+                }
+            }
+        }
+
+        /// Returns the x coordinate and width of the bar at `index`.
+        fn bar_bounds(&self, index: usize) -> (f64, f64) {
+            let count = self.bars.borrow().len().max(1) as f64;
+            let slot_width = (self.width.get() - 2.0 * MARGIN) / count;
+            let bar_width = slot_width * (1.0 - BAR_SPACING);
+            let x = MARGIN + index as f64 * slot_width + (slot_width - bar_width) / 2.0;
+            (x, bar_width)
+        }
+
+        /// Returns the y coordinate and height of every segment of `bar`,
+        /// stacked from the bottom.
+        fn segment_bounds(&self, bar: &BarChartBar) -> Vec<(f64, f64)> {
+            let max_total = self
+                .bars
+                .borrow()
+                .iter()
+                .map(|bar| bar.total())
+                .fold(0.0, f64::max);
+            if max_total <= 0.0 {
+                return vec![(0.0, 0.0); bar.segments.len()];
+            }
+
+            let chart_height = self.height.get() - LABEL_HEIGHT - MARGIN;
+            let mut y = self.height.get() - LABEL_HEIGHT;
+            bar.segments
+                .iter()
+                .map(|segment| {
+                    let segment_height = segment.weight / max_total * chart_height;
+                    y -= segment_height;
+                    (y, segment_height)
+                })
+                .collect()
+        }
+
+        fn highlight_segment_at_point(&self, x: f64, y: f64) {
+            let obj = self.obj();
+            let found = self.segment_at_point(x, y);
+
+            match found {
+                Some((bar_index, segment_index)) => {
+                    let bars = self.bars.borrow();
+                    let bar = &bars[bar_index];
+                    let segment = &bar.segments[segment_index];
+                    let duration = self
+                        .duration_format
+                        .get()
+                        .format(Duration::from_secs_f64(segment.weight));
+                    obj.set_tooltip_text(Some(&format!(
+                        "{}\n{}: {duration}",
+                        segment.title, bar.title
+                    )));
+                    obj.set_has_tooltip(true);
+                }
+                None => {
+                    obj.set_tooltip_text(None);
+                    obj.set_has_tooltip(false);
+                }
+            }
+            self.set_highlighted_segment(found);
+        }
+
+        fn segment_at_point(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+            let bars = self.bars.borrow();
+            for (bar_index, bar) in bars.iter().enumerate() {
+                let (bar_x, bar_width) = self.bar_bounds(bar_index);
+                if x < bar_x || x > bar_x + bar_width {
+                    continue;
+                }
+
+                return self
+                    .segment_bounds(bar)
+                    .into_iter()
+                    .position(|(segment_y, height)| y >= segment_y && y <= segment_y + height)
+                    .map(|segment_index| (bar_index, segment_index));
+            }
+            None
+        }
+
+        fn set_highlighted_segment(&self, highlighted_segment: Option<(usize, usize)>) {
+            if self.highlighted_segment.get() == highlighted_segment {
+                return;
+            }
+
+            self.highlighted_segment.set(highlighted_segment);
+            self.obj().queue_draw();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct BarChart(ObjectSubclass<imp::BarChart>)
+        @extends gtk::Widget, gtk::DrawingArea;
+}
+
+impl BarChart {
+    pub fn set_bars(&self, bars: Vec<BarChartBar>) {
+        let imp = self.imp();
+        {
+            *imp.bars.borrow_mut() = bars;
+        }
+        self.queue_draw();
+    }
+
+    /// Sets how the durations in the tooltips are displayed.
+    pub fn set_duration_format(&self, duration_format: DurationFormat) {
+        self.imp().duration_format.set(duration_format);
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use gtk::gio;
+use gtk::prelude::*;
+use hyprland_app_timer::AppUsage;
+use regex::{Regex, RegexBuilder};
+
+/// Puts every app whose class matches `pattern` in `category`, see the
+/// `app-categories` setting.
+#[derive(Debug, Clone)]
+pub struct CategoryRule {
+    pub pattern: String,
+    pub category: String,
+    /// `pattern` compiled once, `None` when invalid.
+    regex: Option<Regex>,
+}
+
+impl CategoryRule {
+    pub fn new(pattern: &str, category: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            category: category.to_string(),
+            regex: compile(pattern),
+        }
+    }
+
+    /// Parses text like `firefox|chromium = Browsing`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (pattern, category) = text.rsplit_once('=')?;
+        let (pattern, category) = (pattern.trim(), category.trim());
+        if pattern.is_empty() || category.is_empty() {
+            return None;
+        }
+        let rule = Self::new(pattern, category);
+        rule.regex.is_some().then_some(rule)
+    }
+
+    /// Whether the whole `app` class matches the pattern, ignoring case.
+    pub fn matches(&self, app: &str) -> bool {
+        self.regex.as_ref().is_some_and(|regex| regex.is_match(app))
+    }
+}
+
+impl PartialEq for CategoryRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern && self.category == other.category
+    }
+}
+
+impl Eq for CategoryRule {}
+
+/// Usage of the apps of a category, sorted like the apps themselves.
+#[derive(Debug)]
+pub struct CategoryUsage<'a> {
+    pub category: String,
+    pub duration: Duration,
+    pub apps: Vec<&'a AppUsage>,
+}
+
+fn compile(pattern: &str) -> Option<Regex> {
+    RegexBuilder::new(&format!("^(?:{pattern})$"))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Reads the `app-categories` setting, in the order the rules are tried.
+pub fn load(settings: &gio::Settings) -> Vec<CategoryRule> {
+    settings
+        .get::<Vec<(String, String)>>("app-categories")
+        .into_iter()
+        .map(|(pattern, category)| CategoryRule::new(&pattern, &category))
+        .collect()
+}
+
+pub fn store(settings: &gio::Settings, rules: &[CategoryRule]) {
+    let rules: Vec<(String, String)> = rules
+        .iter()
+        .map(|rule| (rule.pattern.clone(), rule.category.clone()))
+        .collect();
+    settings
+        .set("app-categories", rules)
+        .expect("failed to set app categories");
+}
+
+/// Groups `apps_usage` by the category of the first matching rule, putting
+/// the rest in `uncategorized`, with the longest categories first.
+pub fn group<'a>(
+    rules: &[CategoryRule],
+    apps_usage: &'a [AppUsage],
+    uncategorized: &str,
+) -> Vec<CategoryUsage<'a>> {
+    let mut categories: Vec<CategoryUsage> = Vec::new();
+    for app_usage in apps_usage {
+        let category = rules
+            .iter()
+            .find(|rule| rule.matches(&app_usage.app))
+            .map_or(uncategorized, |rule| rule.category.as_str());
+
+        match categories
+            .iter_mut()
+            .find(|usage| usage.category == category)
+        {
+            Some(usage) => {
+                usage.duration += app_usage.duration;
+                usage.apps.push(app_usage);
+            }
+            None => categories.push(CategoryUsage {
+                category: category.to_string(),
+                duration: app_usage.duration,
+                apps: vec![app_usage],
+            }),
+        }
+    }
+    categories.sort_by(|a, b| b.duration.cmp(&a.duration));
+    categories
+}
@@ -0,0 +1,70 @@
+use chrono::{Datelike, NaiveDate};
+
+/// Date range presets in the same order as the header bar dropdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatePreset {
+    Today,
+    Yesterday,
+    ThisWeek,
+    Last7Days,
+    ThisMonth,
+    Last30Days,
+    Custom,
+}
+
+impl DatePreset {
+    pub const ALL: [DatePreset; 7] = [
+        DatePreset::Today,
+        DatePreset::Yesterday,
+        DatePreset::ThisWeek,
+        DatePreset::Last7Days,
+        DatePreset::ThisMonth,
+        DatePreset::Last30Days,
+        DatePreset::Custom,
+    ];
+
+    pub fn from_index(index: u32) -> Self {
+        Self::ALL
+            .get(index as usize)
+            .copied()
+            .unwrap_or(DatePreset::Custom)
+    }
+
+    /// Parses the nick used by the `default-range-preset` setting.
+    pub fn from_nick(nick: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.nick() == nick)
+            .unwrap_or(DatePreset::Custom)
+    }
+
+    pub fn nick(self) -> &'static str {
+        match self {
+            DatePreset::Today => "today",
+            DatePreset::Yesterday => "yesterday",
+            DatePreset::ThisWeek => "this-week",
+            DatePreset::Last7Days => "last-7-days",
+            DatePreset::ThisMonth => "this-month",
+            DatePreset::Last30Days => "last-30-days",
+            DatePreset::Custom => "custom",
+        }
+    }
+
+    /// First and last day (inclusive) of the preset, `None` for `Custom`.
+    pub fn range(self, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let days_ago = |days| today - chrono::Duration::days(days);
+        let range = match self {
+            DatePreset::Today => (today, today),
+            DatePreset::Yesterday => (days_ago(1), days_ago(1)),
+            DatePreset::ThisWeek => (
+                days_ago(today.weekday().num_days_from_monday().into()),
+                today,
+            ),
+            DatePreset::Last7Days => (days_ago(6), today),
+            DatePreset::ThisMonth => (days_ago((today.day() - 1).into()), today),
+            DatePreset::Last30Days => (days_ago(29), today),
+            DatePreset::Custom => return None,
+        };
+        Some(range)
+    }
+}
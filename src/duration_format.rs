@@ -0,0 +1,64 @@
+use std::{cmp::Ordering, time::Duration};
+
+/// How durations are displayed, see the `duration-format` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationFormat {
+    /// `1h 20m 5s`
+    #[default]
+    Precise,
+    /// `1h 20m`
+    Short,
+    /// `1.33 h`
+    Decimal,
+}
+
+impl DurationFormat {
+    pub const ALL: [DurationFormat; 3] = [
+        DurationFormat::Precise,
+        DurationFormat::Short,
+        DurationFormat::Decimal,
+    ];
+
+    pub fn from_nick(nick: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.nick() == nick)
+            .unwrap_or_default()
+    }
+
+    pub fn nick(self) -> &'static str {
+        match self {
+            DurationFormat::Precise => "precise",
+            DurationFormat::Short => "short",
+            DurationFormat::Decimal => "decimal",
+        }
+    }
+
+    pub fn format(self, duration: Duration) -> String {
+        let seconds = duration.as_secs_f64().round() as u64;
+        match self {
+            // round to the nearest minute, but don't hide usage under a minute
+            DurationFormat::Short if seconds >= 60 => {
+                humantime::format_duration(Duration::from_secs((seconds + 30) / 60 * 60))
+                    .to_string()
+            }
+            DurationFormat::Precise | DurationFormat::Short => {
+                humantime::format_duration(Duration::from_secs(seconds)).to_string()
+            }
+            DurationFormat::Decimal => format!("{:.2} h", seconds as f64 / 3600.0),
+        }
+    }
+
+    /// Formats the change from `previous` to `current`, like `+1h 20m ▲` or
+    /// `−15m ▼`.
+    pub fn format_change(self, current: Duration, previous: Duration) -> String {
+        // compare what is displayed, so a change is never shown as +0s
+        let current = Duration::from_secs(current.as_secs_f64().round() as u64);
+        let previous = Duration::from_secs(previous.as_secs_f64().round() as u64);
+        match current.cmp(&previous) {
+            Ordering::Greater => format!("+{} ▲", self.format(current - previous)),
+            Ordering::Less => format!("−{} ▼", self.format(previous - current)),
+            Ordering::Equal => "±0".to_string(),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::{fmt::Write, path::Path, time::Duration};
+
+use chrono::NaiveDate;
+use gtk::cairo;
+use hyprland_app_timer::AppUsage;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Picks the format from the file extension, defaulting to CSV.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    Png,
+    Svg,
+    Pdf,
+}
+
+impl ChartFormat {
+    /// Picks the format from the file extension, defaulting to PNG.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("svg") => ChartFormat::Svg,
+            Some(extension) if extension.eq_ignore_ascii_case("pdf") => ChartFormat::Pdf,
+            _ => ChartFormat::Png,
+        }
+    }
+}
+
+/// Writes what `draw` draws on a `width` by `height` surface to `path`, in
+/// pixels for PNG and points for SVG and PDF.
+pub fn save_chart(
+    format: ChartFormat,
+    path: &Path,
+    width: f64,
+    height: f64,
+    draw: impl Fn(&cairo::Context),
+) -> Result<(), String> {
+    match format {
+        ChartFormat::Png => {
+            let surface =
+                cairo::ImageSurface::create(cairo::Format::ARgb32, width as i32, height as i32)
+                    .map_err(|err| err.to_string())?;
+            draw(&cairo::Context::new(&surface).map_err(|err| err.to_string())?);
+            let mut file = std::fs::File::create(path).map_err(|err| err.to_string())?;
+            surface
+                .write_to_png(&mut file)
+                .map_err(|err| err.to_string())
+        }
+        ChartFormat::Svg => {
+            let surface =
+                cairo::SvgSurface::new(width, height, Some(path)).map_err(|err| err.to_string())?;
+            draw(&cairo::Context::new(&surface).map_err(|err| err.to_string())?);
+            surface.finish();
+            surface.status().map_err(|err| err.to_string())
+        }
+        ChartFormat::Pdf => {
+            let surface =
+                cairo::PdfSurface::new(width, height, path).map_err(|err| err.to_string())?;
+            draw(&cairo::Context::new(&surface).map_err(|err| err.to_string())?);
+            surface.finish();
+            surface.status().map_err(|err| err.to_string())
+        }
+    }
+}
+
+/// Serializes the usage of the days from `start` to `end` (inclusive).
+pub fn export(
+    format: ExportFormat,
+    start: NaiveDate,
+    end: NaiveDate,
+    apps_usage: &[AppUsage],
+) -> String {
+    let apps_usage: Vec<(&str, Duration)> = apps_usage
+        .iter()
+        .map(|app_usage| (app_usage.app.as_str(), app_usage.duration))
+        .collect();
+    export_rows(format, start, end, &apps_usage)
+}
+
+fn export_rows(
+    format: ExportFormat,
+    start: NaiveDate,
+    end: NaiveDate,
+    apps_usage: &[(&str, Duration)],
+) -> String {
+    let total: f64 = apps_usage
+        .iter()
+        .map(|(_, duration)| duration.as_secs_f64())
+        .sum();
+    let rows = apps_usage.iter().map(|(app, duration)| {
+        let seconds = duration.as_secs();
+        let percentage = if total > 0.0 {
+            duration.as_secs_f64() / total * 100.0
+        } else {
+            0.0
+        };
+        let duration = humantime::format_duration(Duration::from_secs(seconds)).to_string();
+        (*app, seconds, duration, percentage)
+    });
+
+    let mut output = String::new();
+    match format {
+        ExportFormat::Csv => {
+            output.push_str("app,seconds,duration,percentage,start,end\n");
+            for (app, seconds, duration, percentage) in rows {
+                writeln!(
+                    output,
+                    "{},{seconds},{},{percentage:.2},{start},{end}",
+                    csv_field(app),
+                    csv_field(&duration)
+                )
+                .unwrap();
+            }
+        }
+        ExportFormat::Json => {
+            writeln!(output, "{{").unwrap();
+            writeln!(output, "  \"start\": \"{start}\",").unwrap();
+            writeln!(output, "  \"end\": \"{end}\",").unwrap();
+            writeln!(output, "  \"total_seconds\": {},", total.round() as u64).unwrap();
+            writeln!(output, "  \"apps\": [").unwrap();
+            let rows: Vec<_> = rows
+                .map(|(app, seconds, duration, percentage)| {
+                    format!(
+                        "    {{\"app\": {}, \"seconds\": {seconds}, \"duration\": {}, \"percentage\": {percentage:.2}}}",
+                        json_string(app),
+                        json_string(&duration)
+                    )
+                })
+                .collect();
+            if !rows.is_empty() {
+                writeln!(output, "{}", rows.join(",\n")).unwrap();
+            }
+            writeln!(output, "  ]").unwrap();
+            writeln!(output, "}}").unwrap();
+        }
+    }
+    output
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len() + 2);
+    escaped.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn plain_csv_fields_are_not_quoted() {
+        assert_eq!(csv_field("firefox"), "firefox");
+    }
+
+    #[test]
+    fn csv_fields_with_commas_are_quoted() {
+        assert_eq!(csv_field("Foo, Inc."), "\"Foo, Inc.\"");
+    }
+
+    #[test]
+    fn csv_quotes_are_doubled() {
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn csv_fields_with_newlines_are_quoted() {
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("two\r\nlines"), "\"two\r\nlines\"");
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("firefox"), "\"firefox\"");
+        assert_eq!(json_string("a, \"b\""), "\"a, \\\"b\\\"\"");
+        assert_eq!(json_string("back\\slash"), "\"back\\\\slash\"");
+        assert_eq!(json_string("two\nlines\t"), "\"two\\nlines\\t\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn csv_rows_quote_app_names() {
+        let output = export_rows(
+            ExportFormat::Csv,
+            day(),
+            day(),
+            &[
+                ("Foo, Inc.", Duration::from_secs(90)),
+                ("say \"hi\"\nnow", Duration::from_secs(30)),
+            ],
+        );
+        assert_eq!(
+            output,
+            "app,seconds,duration,percentage,start,end\n\
+             \"Foo, Inc.\",90,1m 30s,75.00,2024-03-01,2024-03-01\n\
+             \"say \"\"hi\"\"\nnow\",30,30s,25.00,2024-03-01,2024-03-01\n"
+        );
+    }
+
+    #[test]
+    fn json_rows_escape_app_names() {
+        let output = export_rows(
+            ExportFormat::Json,
+            day(),
+            day(),
+            &[("say \"hi\"\nnow", Duration::from_secs(60))],
+        );
+        assert_eq!(
+            output,
+            "{\n  \"start\": \"2024-03-01\",\n  \"end\": \"2024-03-01\",\n  \"total_seconds\": 60,\n  \"apps\": [\n    \
+             {\"app\": \"say \\\"hi\\\"\\nnow\", \"seconds\": 60, \"duration\": \"1m\", \"percentage\": 100.00}\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn empty_usage_has_no_rows() {
+        let output = export_rows(ExportFormat::Json, day(), day(), &[]);
+        assert!(output.contains("\"apps\": [\n  ]"));
+        assert!(output.contains("\"total_seconds\": 0,"));
+        assert_eq!(
+            export_rows(ExportFormat::Csv, day(), day(), &[]),
+            "app,seconds,duration,percentage,start,end\n"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use adw::subclass::prelude::*;
+use chrono::{Datelike, NaiveDate};
+use gettextrs::gettext;
+use gtk::prelude::*;
+use gtk::{cairo, gdk, glib};
+
+use std::time::Duration;
+
+use crate::duration_format::DurationFormat;
+use crate::pie_chart::{item_color, Palette};
+
+mod imp {
+    const MARGIN: f64 = 12.0;
+    const CELL_SIZE: f64 = 12.0;
+    const CELL_SPACING: f64 = 3.0;
+    const MONTH_LABEL_HEIGHT: f64 = 20.0;
+    const WEEKDAY_LABEL_WIDTH: f64 = 36.0;
+    /// Shades used for days with usage, from the lightest.
+    const LEVEL_ALPHAS: [f64; 4] = [0.3, 0.5, 0.75, 1.0];
+    const EMPTY_COLOR: (f64, f64, f64, f64) = (0.5, 0.5, 0.5, 0.15);
+
+    use std::{
+        cell::{Cell, RefCell},
+        sync::OnceLock,
+    };
+
+    use glib::subclass::Signal;
+
+    use super::*;
+
+    #[derive(Debug, Default, glib::Properties)]
+    #[properties(wrapper_type = super::Heatmap)]
+    pub struct Heatmap {
+        /// Usage of consecutive days, the first one in the first column.
+        pub(super) days: RefCell<Vec<(NaiveDate, Duration)>>,
+        /// Days outlined as the selected range.
+        pub(super) selection: Cell<Option<(NaiveDate, NaiveDate)>>,
+        highlighted_day: Cell<Option<NaiveDate>>,
+        pub(super) duration_format: Cell<DurationFormat>,
+
+        #[property(get, set = Self::set_palette, builder(Palette::default()))]
+        palette: Cell<Palette>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Heatmap {
+        const NAME: &'static str = "Heatmap";
+        type Type = super::Heatmap;
+        type ParentType = gtk::DrawingArea;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Heatmap {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                // days since the common era, see `NaiveDate::num_days_from_ce`
+                vec![Signal::builder("day-activated")
+                    .param_types([i32::static_type()])
+                    .build()]
+            })
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_draw_func(glib::clone!(@weak self as widget => move |_, cr, w, h| {
+                widget.draw_heatmap(cr, w, h);
+            }));
+
+            let motion_controller = gtk::EventControllerMotion::new();
+            motion_controller.connect_motion(glib::clone!(@weak self as widget => move |_, x, y| {
+                widget.highlight_day_at_point(x, y);
+            }));
+            motion_controller.connect_leave(glib::clone!(@weak self as widget => move |_| {
+                widget.set_highlighted_day(None);
+            }));
+            obj.add_controller(motion_controller);
+
+            let gesture_click = gtk::GestureClick::new();
+            gesture_click.set_button(gdk::BUTTON_PRIMARY);
+            gesture_click.connect_pressed(glib::clone!(@weak self as widget => move |_, _, x, y| {
+                if let Some(day) = widget.day_at_point(x, y) {
+                    widget
+                        .obj()
+                        .emit_by_name::<()>("day-activated", &[&day.num_days_from_ce()]);
+                }
+            }));
+            obj.add_controller(gesture_click);
+        }
+    }
+    impl WidgetImpl for Heatmap {}
+    impl DrawingAreaImpl for Heatmap {}
+
+    impl Heatmap {
+        fn set_palette(&self, palette: Palette) {
+            self.palette.set(palette);
+            self.obj().queue_draw();
+        }
+
+        /// Sizes the widget to fit every week of the days.
+        pub(super) fn update_size(&self) {
+            let weeks = self.week_count() as f64;
+            let obj = self.obj();
+            obj.set_content_width(
+                (2.0 * MARGIN + WEEKDAY_LABEL_WIDTH + weeks * (CELL_SIZE + CELL_SPACING)) as i32,
+            );
+            obj.set_content_height(
+                (2.0 * MARGIN + MONTH_LABEL_HEIGHT + 7.0 * (CELL_SIZE + CELL_SPACING)) as i32,
+            );
+        }
+
+        fn draw_heatmap(&self, context: &cairo::Context, _width: i32, _height: i32) {
+            let obj = self.obj();
+            let days = self.days.borrow();
+            let max = days
+                .iter()
+                .map(|(_, duration)| *duration)
+                .max()
+                .unwrap_or_default();
+            let color = item_color(self.palette.get(), 0, false);
+            let selection = self.selection.get();
+            let highlighted_day = self.highlighted_day.get();
+
+            context.set_source_rgba(0.5, 0.5, 0.5, 1.0);
+            for (weekday, label) in [
+                (0, gettext("Mon")),
+                (2, gettext("Wed")),
+                (4, gettext("Fri")),
+            ] {
+                let layout = obj.create_pango_layout(Some(&label));
+                let (_, logical) = layout.pixel_extents();
+                let (_, y) = self.cell_origin(0, weekday);
+                context.move_to(MARGIN, y + (CELL_SIZE - logical.height() as f64) / 2.0);
+                pangocairo::functions::show_layout(context, &layout);
+            }
+
+            let mut last_month = None;
+            for (day, duration) in days.iter() {
+                let Some((week, weekday)) = self.cell_position(*day) else {
+                    continue;
+                };
+                let (x, y) = self.cell_origin(week, weekday);
+
+                if last_month != Some(day.month()) {
+                    last_month = Some(day.month());
+                    context.set_source_rgba(0.5, 0.5, 0.5, 1.0);
+                    let layout = obj.create_pango_layout(Some(&day.format("%b").to_string()));
+                    context.move_to(x, MARGIN);
+                    pangocairo::functions::show_layout(context, &layout);
+                }
+
+                match level(*duration, max) {
+                    Some(level) => context.set_source_rgba(
+                        color.red().into(),
+                        color.green().into(),
+                        color.blue().into(),
+                        LEVEL_ALPHAS[level],
+                    ),
+                    None => {
+                        let (red, green, blue, alpha) = EMPTY_COLOR;
+                        context.set_source_rgba(red, green, blue, alpha);
+                    }
+                }
+                context.rectangle(x, y, CELL_SIZE, CELL_SIZE);
+                context.fill().expect("failed to fill");
+
+                let selected = selection.is_some_and(|(start, end)| start <= *day && *day <= end);
+                if selected || highlighted_day == Some(*day) {
+                    GdkCairoContextExt::set_source_rgba(
+                        context,
+                        &item_color(self.palette.get(), 0, true),
+                    );
+                    context.set_line_width(if selected { 2.0 } else { 1.0 });
+                    context.rectangle(x + 0.5, y + 0.5, CELL_SIZE - 1.0, CELL_SIZE - 1.0);
+                    context.stroke().expect("failed to stroke");
+                }
+            }
+        }
+
+        /// Number of columns, each one a week starting on Monday.
+        fn week_count(&self) -> usize {
+            let days = self.days.borrow();
+            match (days.first(), days.last()) {
+                (Some((first, _)), Some((last, _))) => {
+                    let offset = first.weekday().num_days_from_monday() as i64;
+                    ((*last - *first).num_days() + offset) as usize / 7 + 1
+                }
+                _ => 0,
+            }
+        }
+
+        /// Returns the week and weekday of `day`, if shown.
+        fn cell_position(&self, day: NaiveDate) -> Option<(usize, usize)> {
+            let days = self.days.borrow();
+            let (first, _) = days.first()?;
+            let (last, _) = days.last()?;
+            if day < *first || day > *last {
+                return None;
+            }
+            let offset = first.weekday().num_days_from_monday() as i64;
+            let index = (day - *first).num_days() + offset;
+            Some((index as usize / 7, index as usize % 7))
+        }
+
+        fn cell_origin(&self, week: usize, weekday: usize) -> (f64, f64) {
+            (
+                MARGIN + WEEKDAY_LABEL_WIDTH + week as f64 * (CELL_SIZE + CELL_SPACING),
+                MARGIN + MONTH_LABEL_HEIGHT + weekday as f64 * (CELL_SIZE + CELL_SPACING),
+            )
+        }
+
+        fn day_at_point(&self, x: f64, y: f64) -> Option<NaiveDate> {
+            let days = self.days.borrow();
+            days.iter().map(|(day, _)| *day).find(|day| {
+                self.cell_position(*day).is_some_and(|(week, weekday)| {
+                    let (cell_x, cell_y) = self.cell_origin(week, weekday);
+                    x >= cell_x && x <= cell_x + CELL_SIZE && y >= cell_y && y <= cell_y + CELL_SIZE
+                })
+            })
+        }
+
+        fn highlight_day_at_point(&self, x: f64, y: f64) {
+            let obj = self.obj();
+            let found = self.day_at_point(x, y);
+
+            let duration = found.and_then(|found| {
+                self.days
+                    .borrow()
+                    .iter()
+                    .find(|(day, _)| *day == found)
+                    .map(|(_, duration)| *duration)
+            });
+            match (found, duration) {
+                (Some(day), Some(duration)) => {
+                    obj.set_tooltip_text(Some(&format!(
+                        "{}: {}",
+                        day.format("%a %d %b %Y"),
+                        self.duration_format.get().format(duration)
+                    )));
+                    obj.set_has_tooltip(true);
+                    obj.set_cursor(gdk::Cursor::from_name("pointer", None).as_ref());
+                }
+                _ => {
+                    obj.set_tooltip_text(None);
+                    obj.set_has_tooltip(false);
+                    obj.set_cursor(None);
+                }
+            }
+            self.set_highlighted_day(found);
+        }
+
+        fn set_highlighted_day(&self, highlighted_day: Option<NaiveDate>) {
+            if self.highlighted_day.get() == highlighted_day {
+                return;
+            }
+
+            self.highlighted_day.set(highlighted_day);
+            self.obj().queue_draw();
+        }
+    }
+
+    /// Shade of `duration` out of the longest day `max`, `None` when empty.
+    fn level(duration: Duration, max: Duration) -> Option<usize> {
+        if duration.is_zero() || max.is_zero() {
+            return None;
+        }
+        let ratio = duration.as_secs_f64() / max.as_secs_f64();
+        let level = (ratio * LEVEL_ALPHAS.len() as f64).ceil() as usize;
+        Some(level.clamp(1, LEVEL_ALPHAS.len()) - 1)
+    }
+}
+
+glib::wrapper! {
+    /// Grid of days shaded by their usage, one column per week.
+    pub struct Heatmap(ObjectSubclass<imp::Heatmap>)
+        @extends gtk::Widget, gtk::DrawingArea;
+}
+
+impl Heatmap {
+    /// Shows the usage of consecutive `days`, oldest first.
+    pub fn set_days(&self, days: Vec<(NaiveDate, Duration)>) {
+        let imp = self.imp();
+        {
+            *imp.days.borrow_mut() = days;
+        }
+        imp.update_size();
+        self.queue_draw();
+    }
+
+    /// Sets how the durations in the tooltips are displayed.
+    pub fn set_duration_format(&self, duration_format: DurationFormat) {
+        self.imp().duration_format.set(duration_format);
+    }
+
+    /// Outlines the days from `start` to `end` (inclusive).
+    pub fn set_selection(&self, start: NaiveDate, end: NaiveDate) {
+        self.imp().selection.set(Some((start, end)));
+        self.queue_draw();
+    }
+
+    pub fn connect_day_activated<F: Fn(&Self, NaiveDate) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "day-activated",
+            false,
+            glib::closure_local!(move |heatmap: &Self, days_from_ce: i32| {
+                if let Some(day) = NaiveDate::from_num_days_from_ce_opt(days_from_ce) {
+                    f(heatmap, day);
+                }
+            }),
+        )
+    }
+}
@@ -18,15 +18,30 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+mod alias;
+mod app_goal;
+mod app_info;
+mod app_usage_object;
 mod application;
+mod bar_chart;
+mod category;
 mod config;
+mod date_preset;
+mod duration_format;
+mod export;
+mod heatmap;
 mod pie_chart;
+mod pie_layout;
+mod preferences;
+mod sort_mode;
+mod timeline;
+mod usage;
 mod window;
 
 use self::application::HyprlandAppTimerGuiApplication;
 use self::window::HyprlandAppTimerGuiWindow;
 
-use config::{GETTEXT_PACKAGE, LOCALEDIR, PKGDATADIR};
+use config::{APP_ID, GETTEXT_PACKAGE, LOCALEDIR, PKGDATADIR};
 use gettextrs::{bind_textdomain_codeset, bindtextdomain, textdomain};
 use gtk::prelude::*;
 use gtk::{gio, glib};
@@ -47,10 +62,7 @@ fn main() -> glib::ExitCode {
     // Create a new GtkApplication. The application manages our main loop,
     // application windows, integration with the window manager/compositor, and
     // desktop features such as file opening and single-instance applications.
-    let app = HyprlandAppTimerGuiApplication::new(
-        "io.github.zd4y.HyprlandAppTimer",
-        &gio::ApplicationFlags::empty(),
-    );
+    let app = HyprlandAppTimerGuiApplication::new(APP_ID, &gio::ApplicationFlags::empty());
 
     // Run the application. This function will block until the application
     // exits. Upon return, we have our exit code to return to the shell. (This
@@ -1,16 +1,26 @@
 use adw::subclass::prelude::*;
+use gettextrs::gettext;
 use gtk::prelude::*;
 use gtk::{cairo, gdk, glib};
 
-use std::f64::consts::PI;
+use std::time::Duration;
 
-mod imp {
-    const INNER_CIRCLE_RADIUS: f64 = 0.6;
-    const SPACING: f64 = 0.1;
-    const MIN_WEIGHT_RATIO: f64 = 1.0 / 100.0;
-
-    use std::cell::{Cell, RefCell};
+use crate::duration_format::DurationFormat;
+use crate::pie_layout::{self, Color, Ring, RingSlice, SliceAngles, GROUP_COLOR};
 
+mod imp {
+    const DEFAULT_MIN_WEIGHT_RATIO: f64 = 1.0 / 100.0;
+    const DIMMED_ALPHA: f64 = 0.25;
+    const RENDER_MARGIN: f64 = 24.0;
+    const LEGEND_SWATCH_SIZE: f64 = 14.0;
+    const TEXT_COLOR: (f64, f64, f64) = (0.14, 0.14, 0.14);
+
+    use std::{
+        cell::{Cell, RefCell},
+        sync::OnceLock,
+    };
+
+    use glib::subclass::Signal;
     use gtk::pango::FontDescription;
 
     use super::*;
@@ -19,13 +29,30 @@ mod imp {
     #[properties(wrapper_type = super::PieChart)]
     pub struct PieChart {
         pub(super) items: RefCell<Vec<PieChartItem>>,
+        /// Items laid out by the last draw, including the "Other" group.
+        slices: RefCell<Vec<PieChartItem>>,
+        /// How many times the "Other" group has been expanded.
+        pub(super) expanded_depth: Cell<usize>,
         highlighted_item_index: Cell<Option<usize>>,
-        radius: Cell<f64>,
-        width: Cell<f64>,
-        height: Cell<f64>,
+        /// Where the last draw put the ring, for hit testing.
+        ring: Cell<Ring>,
 
         #[property(get, set)]
         title: RefCell<Option<String>>,
+        /// Smaller text drawn under the title.
+        #[property(get, set = Self::set_subtitle)]
+        subtitle: RefCell<Option<String>>,
+        /// Items below this share of the total are grouped into "Other".
+        #[property(get, set = Self::set_min_weight_ratio, minimum = 0.0, maximum = 1.0, default = DEFAULT_MIN_WEIGHT_RATIO)]
+        min_weight_ratio: Cell<f64>,
+        #[property(get, set = Self::set_palette, builder(Palette::default()))]
+        palette: Cell<Palette>,
+        /// Title of the item drawn as selected, even when not hovered.
+        #[property(get, set = Self::set_selected_title)]
+        selected_title: RefCell<Option<String>>,
+        /// Items not matching this text are dimmed.
+        #[property(get, set = Self::set_search_text)]
+        search_text: RefCell<Option<String>>,
     }
 
     #[glib::object_subclass]
@@ -37,8 +64,18 @@ mod imp {
 
     #[glib::derived_properties]
     impl ObjectImpl for PieChart {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("item-activated")
+                    .param_types([PieChartItem::static_type()])
+                    .build()]
+            })
+        }
+
         fn constructed(&self) {
             self.parent_constructed();
+            self.min_weight_ratio.set(DEFAULT_MIN_WEIGHT_RATIO);
             let obj = self.obj();
             obj.set_draw_func(glib::clone!(@weak self as widget => move |_, cr, w, h| {
                 widget.draw_chart(cr, w, h);
@@ -54,7 +91,13 @@ mod imp {
             gesture_click.set_button(gdk::BUTTON_PRIMARY);
             gesture_click.connect_pressed(glib::clone!(@weak self as widget => move |_, _, x, y| {
                 if let Some(item) = widget.highlight_item_at_point(x, y) {
-                    println!("clicked: {}", item.title());
+                    if item.is_group() {
+                        widget.expand_group();
+                    } else {
+                        widget.obj().emit_by_name::<()>("item-activated", &[&item]);
+                    }
+                } else if widget.point_in_center(x, y) {
+                    widget.collapse_group();
                 }
             }));
             obj.add_controller(gesture_click);
@@ -65,81 +108,304 @@ mod imp {
 
     impl PieChart {
         fn draw_chart(&self, context: &cairo::Context, width: i32, height: i32) {
-            let width_f64: f64 = width.into();
-            let height_f64: f64 = height.into();
-            let radius = width_f64.min(height_f64) / 2.0;
+            let ring = Ring::fit(width.into(), height.into());
+            let obj = self.obj();
+            GdkCairoContextExt::set_source_rgba(context, &obj.color());
+            self.draw_title(context, ring.xc, ring.yc, &|text| {
+                obj.create_pango_layout(Some(text))
+            });
+            self.ring.set(ring);
+
+            // keep the same item highlighted if the items changed under the pointer
+            let highlighted_title = self
+                .highlighted_item_index
+                .get()
+                .and_then(|index| self.slices.borrow().get(index).map(|item| item.title()));
+            let slices = self.layout_items();
+            let highlighted_item_index = highlighted_title
+                .and_then(|title| slices.iter().position(|item| item.title() == title));
+            self.highlighted_item_index.set(highlighted_item_index);
+
+            self.draw_slices(context, &slices, ring, true);
 
-            let xc = width_f64 / 2.0;
-            let yc = height_f64 / 2.0;
+            *self.slices.borrow_mut() = slices;
+        }
 
-            let pango_layout = self
-                .obj()
-                .create_pango_layout(self.title.borrow().as_deref());
+        /// Draws the title, and the subtitle under it, centered on `xc`, `yc`,
+        /// with the layouts made by `create_layout`.
+        fn draw_title(
+            &self,
+            context: &cairo::Context,
+            xc: f64,
+            yc: f64,
+            create_layout: &dyn Fn(&str) -> gtk::pango::Layout,
+        ) {
+            let pango_layout = create_layout(self.title.borrow().as_deref().unwrap_or_default());
             pango_layout.set_alignment(gtk::pango::Alignment::Center);
             pango_layout.set_font_description(Some(&FontDescription::from_string("Sans Thin 32")));
             let (pe, _) = pango_layout.pixel_extents();
             context.move_to(xc - (pe.width() as f64 / 2.0), yc - pe.height() as f64);
             pangocairo::functions::show_layout(context, &pango_layout);
 
-            context.new_path();
-
-            self.radius.set(radius);
-            self.width.set(width_f64);
-            self.height.set(height_f64);
+            if let Some(subtitle) = self.subtitle.borrow().as_deref() {
+                let subtitle_layout = create_layout(subtitle);
+                subtitle_layout.set_alignment(gtk::pango::Alignment::Center);
+                subtitle_layout
+                    .set_font_description(Some(&FontDescription::from_string("Sans 12")));
+                let (pe, _) = subtitle_layout.pixel_extents();
+                context.move_to(xc - (pe.width() as f64 / 2.0), yc);
+                pangocairo::functions::show_layout(context, &subtitle_layout);
+            }
 
-            let spacing = SPACING / (2.0 * PI);
+            context.new_path();
+        }
 
-            let items = self.items.borrow_mut();
+        /// Draws `slices` as `ring`, storing the angles of each. `interactive`
+        /// also draws the highlighted, selected and searched items.
+        fn draw_slices(
+            &self,
+            context: &cairo::Context,
+            slices: &[PieChartItem],
+            ring: Ring,
+            interactive: bool,
+        ) {
             let highlighted_item_index = self.highlighted_item_index.get();
-
-            let total: f64 = items.iter().map(|item| item.weight()).sum();
-            let new_items: Vec<_> = items
-                .iter()
-                .filter(|item| item.weight() / total >= MIN_WEIGHT_RATIO)
+            let search_text = self.search_text.borrow();
+            let weights: Vec<f64> = slices.iter().map(|item| item.weight()).collect();
+
+            let ring_slices: Vec<RingSlice> = pie_layout::slice_angles(&weights)
+                .into_iter()
+                .zip(slices)
+                .enumerate()
+                .map(|(index, (angles, item))| {
+                    item.set_start_angle(angles.start);
+                    item.set_end_angle(angles.end);
+
+                    let highlighted = interactive
+                        && (highlighted_item_index == Some(index) || self.is_selected(item));
+                    let mut color = self.slice_color(item, highlighted);
+                    if interactive
+                        && search_text
+                            .as_deref()
+                            .is_some_and(|text| !item.matches(text))
+                    {
+                        color.alpha = DIMMED_ALPHA;
+                    }
+
+                    RingSlice {
+                        angles,
+                        color,
+                        exceeded: item.exceeded(),
+                    }
+                })
                 .collect();
-            let total: f64 = new_items.iter().map(|item| item.weight()).sum();
 
-            let mut acc = 0.0;
-            for (index, item) in new_items.iter().enumerate() {
-                let weight = item.weight();
-                let weight_ratio = weight / total;
+            pie_layout::draw_ring(context, ring, &ring_slices);
+        }
+
+        /// Draws `heading`, the chart and a legend of its slices on a white
+        /// `width` by `height` area, for saving the chart to a file. The
+        /// legend wraps into columns, leaving out the slices that don't fit.
+        pub(super) fn render(
+            &self,
+            context: &cairo::Context,
+            width: f64,
+            height: f64,
+            heading: &str,
+            duration_format: DurationFormat,
+        ) {
+            context.set_source_rgb(1.0, 1.0, 1.0);
+            context.paint().expect("failed to paint");
+            context.set_source_rgba(TEXT_COLOR.0, TEXT_COLOR.1, TEXT_COLOR.2, 1.0);
+
+            let heading_layout = pangocairo::functions::create_layout(context);
+            heading_layout.set_text(heading);
+            heading_layout
+                .set_font_description(Some(&FontDescription::from_string("Sans Bold 20")));
+            let (_, heading_extents) = heading_layout.pixel_extents();
+            context.move_to(RENDER_MARGIN, RENDER_MARGIN);
+            pangocairo::functions::show_layout(context, &heading_layout);
+
+            let top = 2.0 * RENDER_MARGIN + heading_extents.height() as f64;
+            let size = (height - top - RENDER_MARGIN).min(width / 2.0).max(0.0);
+            let radius = size / 2.0;
+            let ring = Ring {
+                xc: RENDER_MARGIN + radius,
+                yc: top + radius,
+                radius,
+            };
 
-                let highlighted = if let Some(highlighted_idx) = highlighted_item_index {
-                    index == highlighted_idx
+            self.draw_title(context, ring.xc, ring.yc, &|text| {
+                let layout = pangocairo::functions::create_layout(context);
+                layout.set_text(text);
+                layout
+            });
+            let slices = self.layout_items();
+            self.draw_slices(context, &slices, ring, false);
+
+            let total: f64 = slices.iter().map(|item| item.weight()).sum();
+            let mut legend_x = ring.xc + radius + 2.0 * RENDER_MARGIN;
+            let mut legend_y = top;
+            let mut column_width: f64 = 0.0;
+            for item in &slices {
+                let percentage = if total > 0.0 {
+                    item.weight() / total * 100.0
                 } else {
-                    false
+                    0.0
                 };
+                let duration = duration_format.format(Duration::from_secs_f64(item.weight()));
+                let layout = pangocairo::functions::create_layout(context);
+                layout.set_text(&format!("{}  {duration} ({percentage:.1}%)", item.label()));
+                layout.set_font_description(Some(&FontDescription::from_string("Sans 12")));
+                let (_, extents) = layout.pixel_extents();
+                let row_height = (extents.height() as f64).max(LEGEND_SWATCH_SIZE);
+
+                if legend_y > top && legend_y + row_height > height - RENDER_MARGIN {
+                    legend_x += column_width + RENDER_MARGIN;
+                    legend_y = top;
+                    column_width = 0.0;
+                }
+                let row_width = LEGEND_SWATCH_SIZE + 8.0 + extents.width() as f64;
+                if legend_x + row_width > width - RENDER_MARGIN {
+                    break;
+                }
+                column_width = column_width.max(row_width);
+
+                let color = self.slice_color(item, false);
+                context.set_source_rgba(color.red, color.green, color.blue, color.alpha);
+                context.rectangle(
+                    legend_x,
+                    legend_y + 2.0,
+                    LEGEND_SWATCH_SIZE,
+                    LEGEND_SWATCH_SIZE,
+                );
+                context.fill().expect("failed to fill");
+
+                context.set_source_rgba(TEXT_COLOR.0, TEXT_COLOR.1, TEXT_COLOR.2, 1.0);
+                context.move_to(legend_x + LEGEND_SWATCH_SIZE + 8.0, legend_y);
+                pangocairo::functions::show_layout(context, &layout);
 
-                let color = self.get_item_color(index, highlighted);
-                GdkCairoContextExt::set_source_rgba(context, &color);
+                legend_y += row_height + 8.0;
+            }
+        }
 
-                let from_angle = acc + spacing;
-                let to_angle = acc + weight_ratio * (2.0 * PI);
-                context.arc(xc, yc, radius, from_angle, to_angle);
+        /// Returns the items to draw at the current expansion depth, with every
+        /// item below `min_weight_ratio` grouped into a single "Other" item.
+        fn layout_items(&self) -> Vec<PieChartItem> {
+            let items = self.items.borrow();
+            let weights: Vec<f64> = items.iter().map(|item| item.weight()).collect();
+            let grouping = pie_layout::group_small(
+                &weights,
+                self.min_weight_ratio.get(),
+                self.expanded_depth.get(),
+            );
+            self.expanded_depth.set(grouping.depth);
+
+            let mut slices: Vec<PieChartItem> = grouping
+                .shown
+                .iter()
+                .map(|index| items[*index].clone())
+                .collect();
+            if !grouping.other.is_empty() {
+                let other: Vec<PieChartItem> = grouping
+                    .other
+                    .iter()
+                    .map(|index| items[*index].clone())
+                    .collect();
+                slices.push(PieChartItem::new_group(&gettext("Other"), &other));
+            }
+            slices
+        }
 
-                item.set_start_angle(from_angle);
-                item.set_end_angle(to_angle);
+        fn expand_group(&self) {
+            self.expanded_depth.set(self.expanded_depth.get() + 1);
+            self.set_highlighted_item_index(None);
+            self.obj().queue_draw();
+        }
 
-                let new_radius = radius * INNER_CIRCLE_RADIUS;
-                let new_x = new_radius * to_angle.cos() + xc;
-                let new_y = new_radius * to_angle.sin() + yc;
-                context.line_to(new_x, new_y);
-                context.arc_negative(xc, yc, new_radius, to_angle, from_angle);
-                context.fill().expect("failed to fill");
-                acc = to_angle;
+        fn collapse_group(&self) {
+            let depth = self.expanded_depth.get();
+            if depth == 0 {
+                return;
+            }
+            self.expanded_depth.set(depth - 1);
+            self.set_highlighted_item_index(None);
+            self.obj().queue_draw();
+        }
+
+        fn set_min_weight_ratio(&self, min_weight_ratio: f64) {
+            self.min_weight_ratio.set(min_weight_ratio);
+            self.obj().queue_draw();
+        }
+
+        fn set_palette(&self, palette: Palette) {
+            self.palette.set(palette);
+            self.obj().queue_draw();
+        }
+
+        fn set_selected_title(&self, selected_title: Option<String>) {
+            if *self.selected_title.borrow() == selected_title {
+                return;
             }
+
+            self.selected_title.replace(selected_title);
+            self.obj().queue_draw();
+        }
+
+        fn set_subtitle(&self, subtitle: Option<String>) {
+            self.subtitle.replace(subtitle);
+            self.obj().queue_draw();
+        }
+
+        fn set_search_text(&self, search_text: Option<String>) {
+            self.search_text.replace(search_text);
+            self.obj().queue_draw();
+        }
+
+        /// Whether `item` is, or is a group containing, the selected item.
+        fn is_selected(&self, item: &PieChartItem) -> bool {
+            let selected_title = self.selected_title.borrow();
+            let Some(selected_title) = selected_title.as_deref() else {
+                return false;
+            };
+
+            item.title() == selected_title
+                || item.children().iter().any(|child| self.is_selected(child))
+        }
+
+        fn point_in_center(&self, x: f64, y: f64) -> bool {
+            self.ring.get().contains_center(x, y)
         }
 
         fn highlight_item_at_point(&self, x: f64, y: f64) -> Option<PieChartItem> {
             let obj = self.obj();
-            for (index, item) in self.items.borrow().iter().enumerate() {
-                if self.item_at_point(item, x, y) {
-                    obj.set_tooltip_text(Some(&item.title()));
-                    obj.set_has_tooltip(true);
-                    obj.set_cursor(gdk::Cursor::from_name("pointer", None).as_ref());
-                    self.set_highlighted_item_index(Some(index));
-                    return Some(item.clone());
-                }
+            let found = {
+                let slices = self.slices.borrow();
+                let angles: Vec<SliceAngles> = slices
+                    .iter()
+                    .map(|item| SliceAngles {
+                        start: item.start_angle(),
+                        end: item.end_angle(),
+                    })
+                    .collect();
+                self.ring
+                    .get()
+                    .slice_at_point(&angles, x, y)
+                    .map(|index| (index, slices[index].clone()))
+            };
+            if let Some((index, item)) = found {
+                obj.set_tooltip_text(Some(&item.tooltip()));
+                obj.set_has_tooltip(true);
+                obj.set_cursor(gdk::Cursor::from_name("pointer", None).as_ref());
+                self.set_highlighted_item_index(Some(index));
+                return Some(item);
+            }
+            if self.expanded_depth.get() > 0 && self.point_in_center(x, y) {
+                obj.set_tooltip_text(Some(&gettext("Go back")));
+                obj.set_has_tooltip(true);
+                obj.set_cursor(gdk::Cursor::from_name("pointer", None).as_ref());
+                self.set_highlighted_item_index(None);
+                return None;
             }
             obj.set_tooltip_text(None);
             obj.set_has_tooltip(false);
@@ -157,67 +423,126 @@ mod imp {
             self.obj().queue_draw();
         }
 
-        fn get_item_color(&self, index: usize, highlighted: bool) -> gdk::RGBA {
-            let color = match index % 6 {
-                0 => "#e01b24",
-                1 => "#ff7800",
-                2 => "#f6d32d",
-                3 => "#33d17a",
-                4 => "#3584e4",
-                _ => "#9141ac",
-            };
-            let mut rgba = gdk::RGBA::parse(color).unwrap();
-            if highlighted {
-                rgba.set_red(rgba.red() / 1.1);
-                rgba.set_blue(rgba.blue() / 1.1);
-                rgba.set_green(rgba.green() / 1.1);
+        fn slice_color(&self, item: &PieChartItem, highlighted: bool) -> Color {
+            if item.is_group() {
+                Color::parse(GROUP_COLOR)
+                    .expect("invalid group color")
+                    .shaded(highlighted)
+            } else {
+                pie_layout::slice_color(
+                    self.palette.get().colors(),
+                    item.imp().color_index.get(),
+                    highlighted,
+                )
             }
-            rgba
         }
+    }
+}
 
-        fn item_at_point(&self, item: &PieChartItem, x: f64, y: f64) -> bool {
-            let max_radius = self.radius.get();
-            let xc = self.width.get() / 2.0;
-            let yc = self.height.get() / 2.0;
-            let from_angle: f64 = item.start_angle();
-            let to_angle: f64 = item.end_angle();
+/// Set of colors the charts pick their items' colors from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "PieChartPalette")]
+pub enum Palette {
+    #[default]
+    Default,
+    Pastel,
+    Colorblind,
+}
 
-            let x = x - xc;
-            let y = y - yc;
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Default, Palette::Pastel, Palette::Colorblind];
 
-            let distance = (x.powi(2) + y.powi(2)).sqrt();
-            let mut angle = y.atan2(x);
-            if angle < 0.0 {
-                angle += 2.0 * PI;
-            }
+    /// Nick used by the `color-palette` setting.
+    pub fn nick(self) -> &'static str {
+        match self {
+            Palette::Default => "default",
+            Palette::Pastel => "pastel",
+            Palette::Colorblind => "colorblind",
+        }
+    }
 
-            distance <= max_radius
-                && distance >= (max_radius * INNER_CIRCLE_RADIUS)
-                && angle >= from_angle
-                && angle <= to_angle
+    fn colors(self) -> &'static [&'static str] {
+        match self {
+            Palette::Default => &[
+                "#e01b24", "#ff7800", "#f6d32d", "#33d17a", "#3584e4", "#9141ac",
+            ],
+            Palette::Pastel => &[
+                "#f66151", "#ffa348", "#f9f06b", "#8ff0a4", "#99c1f1", "#dc8add",
+            ],
+            // Okabe-Ito palette
+            Palette::Colorblind => &[
+                "#e69f00", "#56b4e9", "#009e73", "#f0e442", "#0072b2", "#d55e00", "#cc79a7",
+            ],
         }
     }
 }
 
+/// Returns the color of the chart item at `index`, shared by every chart so
+/// the same app keeps its color across views.
+pub fn item_color(palette: Palette, index: usize, highlighted: bool) -> gdk::RGBA {
+    let color = pie_layout::slice_color(palette.colors(), index, highlighted);
+    gdk::RGBA::new(
+        color.red as f32,
+        color.green as f32,
+        color.blue as f32,
+        color.alpha as f32,
+    )
+}
+
 glib::wrapper! {
     pub struct PieChart(ObjectSubclass<imp::PieChart>)
         @extends gtk::Widget, gtk::DrawingArea;
 }
 
 impl PieChart {
-    pub fn add_item(&self, item: &PieChartItem) {
+    /// Replaces the items, keeping the expanded "Other" group and the
+    /// highlighted item so periodic updates don't disturb the user.
+    pub fn set_items(&self, items: &[PieChartItem]) {
         let imp = self.imp();
         {
-            imp.items.borrow_mut().push(item.clone());
+            let mut current_items = imp.items.borrow_mut();
+            current_items.clear();
+            for (index, item) in items.iter().enumerate() {
+                item.imp().color_index.set(index);
+                current_items.push(item.clone());
+            }
         }
         self.queue_draw();
     }
 
+    /// Draws the chart with `heading` above it and a legend beside it on
+    /// any context, such as one of an image, SVG or PDF surface.
+    pub fn render(
+        &self,
+        context: &cairo::Context,
+        width: f64,
+        height: f64,
+        heading: &str,
+        duration_format: DurationFormat,
+    ) {
+        self.imp()
+            .render(context, width, height, heading, duration_format);
+    }
+
+    pub fn connect_item_activated<F: Fn(&Self, &PieChartItem) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "item-activated",
+            false,
+            glib::closure_local!(move |chart: &Self, item: &PieChartItem| {
+                f(chart, item);
+            }),
+        )
+    }
+
     pub fn clear(&self) {
         let imp = self.imp();
         {
             imp.items.borrow_mut().clear();
         }
+        imp.expanded_depth.set(0);
         self.queue_draw();
     }
 }
@@ -232,12 +557,23 @@ mod imp2 {
     pub struct PieChartItem {
         #[property(get, set)]
         title: RefCell<String>,
+        /// Shown in the tooltip instead of the title when set.
+        #[property(get, set)]
+        display_name: RefCell<Option<String>>,
         #[property(get, set)]
         weight: Cell<f64>,
         #[property(get, set)]
         start_angle: Cell<f64>,
         #[property(get, set)]
         end_angle: Cell<f64>,
+        /// Whether the item went over its limit, outlined in red.
+        #[property(get, set)]
+        exceeded: Cell<bool>,
+
+        pub(super) children: RefCell<Vec<super::PieChartItem>>,
+        /// Position of the item when it was added, so its color doesn't
+        /// change when other items are grouped.
+        pub(super) color_index: Cell<usize>,
     }
 
     #[glib::object_subclass]
@@ -261,4 +597,48 @@ impl PieChartItem {
             .property("weight", weight)
             .build()
     }
+
+    /// Creates an item that stands for all of `children` combined.
+    pub fn new_group(title: &str, children: &[PieChartItem]) -> Self {
+        let weight = children.iter().map(|child| child.weight()).sum();
+        let item = Self::new(title, weight);
+        item.set_exceeded(children.iter().any(|child| child.exceeded()));
+        *item.imp().children.borrow_mut() = children.to_vec();
+        item
+    }
+
+    pub fn is_group(&self) -> bool {
+        !self.imp().children.borrow().is_empty()
+    }
+
+    pub fn children(&self) -> Vec<PieChartItem> {
+        self.imp().children.borrow().clone()
+    }
+
+    fn tooltip(&self) -> String {
+        let children = self.imp().children.borrow();
+        if children.is_empty() {
+            return self.label();
+        }
+
+        let mut tooltip = self.label();
+        for child in children.iter() {
+            tooltip.push_str("\n• ");
+            tooltip.push_str(&child.label());
+        }
+        tooltip
+    }
+
+    /// Whether the title or display name of the item, or of any of its
+    /// children, contains `text`, ignoring case.
+    fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.title().to_lowercase().contains(&text)
+            || self.label().to_lowercase().contains(&text)
+            || self.children().iter().any(|child| child.matches(&text))
+    }
+
+    fn label(&self) -> String {
+        self.display_name().unwrap_or_else(|| self.title())
+    }
 }
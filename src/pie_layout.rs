@@ -0,0 +1,512 @@
+use std::f64::consts::PI;
+
+use gtk::cairo;
+
+/// Radius of the hole in the middle of the ring, relative to its radius.
+pub const INNER_CIRCLE_RADIUS: f64 = 0.6;
+/// Gap before every slice, in radians once divided by 2π.
+pub const SPACING: f64 = 0.1;
+/// Outline of the slices of apps over their limit.
+pub const EXCEEDED_COLOR: &str = "#e01b24";
+pub const EXCEEDED_LINE_WIDTH: f64 = 3.0;
+/// Color of the "Other" group.
+pub const GROUP_COLOR: &str = "#9a9996";
+
+/// Angles of a slice in radians, clockwise from the positive x axis.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SliceAngles {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Lays out one slice per weight in order, each taking its share of a full
+/// turn minus the gap before it.
+pub fn slice_angles(weights: &[f64]) -> Vec<SliceAngles> {
+    let total: f64 = weights.iter().sum();
+    let spacing = SPACING / (2.0 * PI);
+
+    let mut acc = 0.0;
+    weights
+        .iter()
+        .map(|weight| {
+            let end = if total > 0.0 {
+                acc + weight / total * (2.0 * PI)
+            } else {
+                acc
+            };
+            // Slices thinner than the spacing would otherwise wrap around
+            let start = (acc + spacing).min(end);
+            acc = end;
+            SliceAngles { start, end }
+        })
+        .collect()
+}
+
+/// Which items are drawn as their own slice and which are grouped into
+/// "Other", see [`group_small`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Grouping {
+    /// Indices of the items drawn as their own slice, in order.
+    pub shown: Vec<usize>,
+    /// Indices of the items grouped into "Other", never a single one.
+    pub other: Vec<usize>,
+    /// Depth actually expanded, lower than asked when nothing was left to
+    /// expand into.
+    pub depth: usize,
+}
+
+/// Groups the items below `min_ratio` of the total into "Other", after
+/// expanding "Other" `depth` times, each time laying out only the items
+/// that were grouped.
+pub fn group_small(weights: &[f64], min_ratio: f64, depth: usize) -> Grouping {
+    let mut items: Vec<usize> = (0..weights.len()).collect();
+
+    for current_depth in 0..=depth {
+        let total: f64 = items.iter().map(|index| weights[*index]).sum();
+        let (shown, small): (Vec<_>, Vec<_>) = items
+            .iter()
+            .partition(|index| total <= 0.0 || weights[**index] / total >= min_ratio);
+
+        if current_depth == depth {
+            return match small.len() {
+                0 | 1 => Grouping {
+                    shown: shown.into_iter().chain(small).collect(),
+                    other: Vec::new(),
+                    depth,
+                },
+                _ => Grouping {
+                    shown,
+                    other: small,
+                    depth,
+                },
+            };
+        } else if small.len() > 1 {
+            items = small;
+        } else {
+            // Nothing left to expand into, stay at this depth
+            return Grouping {
+                shown: shown.into_iter().chain(small).collect(),
+                other: Vec::new(),
+                depth: current_depth,
+            };
+        }
+    }
+
+    unreachable!("the last depth always returns")
+}
+
+/// Where the ring is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Ring {
+    pub xc: f64,
+    pub yc: f64,
+    pub radius: f64,
+}
+
+impl Ring {
+    /// The largest ring centered in a `width` by `height` area.
+    pub fn fit(width: f64, height: f64) -> Self {
+        Self {
+            xc: width / 2.0,
+            yc: height / 2.0,
+            radius: width.min(height) / 2.0,
+        }
+    }
+
+    pub fn inner_radius(&self) -> f64 {
+        self.radius * INNER_CIRCLE_RADIUS
+    }
+
+    /// Whether `x`, `y` is in the hole in the middle of the ring.
+    pub fn contains_center(&self, x: f64, y: f64) -> bool {
+        self.distance(x, y) < self.inner_radius()
+    }
+
+    /// Index of the slice under `x`, `y`, if any.
+    pub fn slice_at_point(&self, slices: &[SliceAngles], x: f64, y: f64) -> Option<usize> {
+        let distance = self.distance(x, y);
+        if distance > self.radius || distance < self.inner_radius() {
+            return None;
+        }
+
+        let mut angle = (y - self.yc).atan2(x - self.xc);
+        if angle < 0.0 {
+            angle += 2.0 * PI;
+        }
+        slices
+            .iter()
+            .position(|slice| angle >= slice.start && angle <= slice.end)
+    }
+
+    fn distance(&self, x: f64, y: f64) -> f64 {
+        ((x - self.xc).powi(2) + (y - self.yc).powi(2)).sqrt()
+    }
+}
+
+/// Color with components from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+    pub alpha: f64,
+}
+
+impl Color {
+    /// Parses colors like `#3584e4`.
+    pub fn parse(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#')?;
+        if hex.len() != 6 {
+            return None;
+        }
+        let component = |index: usize| {
+            u8::from_str_radix(hex.get(index..index + 2)?, 16)
+                .ok()
+                .map(|value| f64::from(value) / 255.0)
+        };
+        Some(Self {
+            red: component(0)?,
+            green: component(2)?,
+            blue: component(4)?,
+            alpha: 1.0,
+        })
+    }
+
+    /// Darkens the color of highlighted items.
+    pub fn shaded(self, highlighted: bool) -> Self {
+        if !highlighted {
+            return self;
+        }
+        Self {
+            red: self.red / 1.1,
+            green: self.green / 1.1,
+            blue: self.blue / 1.1,
+            alpha: self.alpha,
+        }
+    }
+}
+
+/// Color of the item at `index`, cycling through `colors`.
+pub fn slice_color(colors: &[&str], index: usize, highlighted: bool) -> Color {
+    let color = Color::parse(colors[index % colors.len()]).expect("invalid palette color");
+    color.shaded(highlighted)
+}
+
+/// A slice as drawn by [`draw_ring`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RingSlice {
+    pub angles: SliceAngles,
+    pub color: Color,
+    /// Whether the slice is outlined with [`EXCEEDED_COLOR`].
+    pub exceeded: bool,
+}
+
+/// Draws `slices` as a ring, without needing a widget or a display.
+pub fn draw_ring(context: &cairo::Context, ring: Ring, slices: &[RingSlice]) {
+    let exceeded_color = Color::parse(EXCEEDED_COLOR).expect("invalid exceeded color");
+    for slice in slices {
+        let SliceAngles { start, end } = slice.angles;
+        let color = slice.color;
+        context.set_source_rgba(color.red, color.green, color.blue, color.alpha);
+
+        context.arc(ring.xc, ring.yc, ring.radius, start, end);
+        let inner_radius = ring.inner_radius();
+        context.line_to(
+            inner_radius * end.cos() + ring.xc,
+            inner_radius * end.sin() + ring.yc,
+        );
+        context.arc_negative(ring.xc, ring.yc, inner_radius, end, start);
+        context.close_path();
+        context.fill_preserve().expect("failed to fill");
+
+        if slice.exceeded {
+            context.set_source_rgba(
+                exceeded_color.red,
+                exceeded_color.green,
+                exceeded_color.blue,
+                exceeded_color.alpha,
+            );
+            context.set_line_width(EXCEEDED_LINE_WIDTH);
+            context.stroke().expect("failed to stroke");
+        } else {
+            context.new_path();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, path::PathBuf};
+
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+    const COLORS: [&str; 3] = ["#e01b24", "#33d17a", "#3584e4"];
+
+    fn spacing() -> f64 {
+        SPACING / (2.0 * PI)
+    }
+
+    #[test]
+    fn slices_fill_a_full_turn() {
+        let slices = slice_angles(&[1.0, 1.0, 2.0]);
+        assert_eq!(slices.len(), 3);
+        assert!((slices[0].end - PI / 2.0).abs() < EPSILON);
+        assert!((slices[1].end - PI).abs() < EPSILON);
+        assert!((slices[2].end - 2.0 * PI).abs() < EPSILON);
+    }
+
+    #[test]
+    fn slices_start_after_a_gap() {
+        let slices = slice_angles(&[1.0, 1.0]);
+        assert!((slices[0].start - spacing()).abs() < EPSILON);
+        assert!((slices[1].start - (slices[0].end + spacing())).abs() < EPSILON);
+    }
+
+    #[test]
+    fn thin_slices_do_not_wrap_around() {
+        let slices = slice_angles(&[1000.0, 0.001]);
+        assert!(slices[1].start <= slices[1].end);
+        assert_eq!(slices[1].start, slices[1].end);
+    }
+
+    #[test]
+    fn zero_weights_give_empty_slices() {
+        let slices = slice_angles(&[0.0, 0.0]);
+        for slice in slices {
+            assert!(!slice.start.is_nan() && !slice.end.is_nan());
+            assert_eq!(slice.start, slice.end);
+        }
+    }
+
+    #[test]
+    fn hit_testing_finds_the_slice_in_each_quadrant() {
+        let ring = Ring::fit(200.0, 200.0);
+        let slices = slice_angles(&[1.0, 1.0, 1.0, 1.0]);
+        let distance = 80.0;
+        let at_angle = |angle: f64| {
+            (
+                ring.xc + distance * angle.cos(),
+                ring.yc + distance * angle.sin(),
+            )
+        };
+
+        for (index, angle) in [PI / 4.0, 3.0 * PI / 4.0, 5.0 * PI / 4.0, 7.0 * PI / 4.0]
+            .into_iter()
+            .enumerate()
+        {
+            let (x, y) = at_angle(angle);
+            assert_eq!(ring.slice_at_point(&slices, x, y), Some(index));
+        }
+    }
+
+    #[test]
+    fn hit_testing_handles_angles_just_below_a_full_turn() {
+        let ring = Ring::fit(200.0, 200.0);
+        let slices = slice_angles(&[1.0, 1.0]);
+        // atan2 is negative above the center
+        let (x, y) = (ring.xc + 80.0, ring.yc - 1.0);
+        assert_eq!(ring.slice_at_point(&slices, x, y), Some(1));
+    }
+
+    #[test]
+    fn hit_testing_misses_the_gaps_the_hole_and_the_outside() {
+        let ring = Ring::fit(200.0, 200.0);
+        let slices = slice_angles(&[1.0, 1.0]);
+
+        let gap = spacing() / 2.0;
+        let (x, y) = (ring.xc + 80.0 * gap.cos(), ring.yc + 80.0 * gap.sin());
+        assert_eq!(ring.slice_at_point(&slices, x, y), None);
+
+        assert_eq!(ring.slice_at_point(&slices, ring.xc, ring.yc + 10.0), None);
+        assert!(ring.contains_center(ring.xc, ring.yc + 10.0));
+
+        assert_eq!(ring.slice_at_point(&slices, ring.xc, ring.yc + 101.0), None);
+        assert!(!ring.contains_center(ring.xc, ring.yc + 101.0));
+    }
+
+    #[test]
+    fn hit_testing_follows_an_off_center_ring() {
+        let ring = Ring::fit(400.0, 200.0);
+        assert_eq!(ring.radius, 100.0);
+        let slices = slice_angles(&[1.0]);
+        assert_eq!(ring.slice_at_point(&slices, 200.0, 180.0), Some(0));
+        assert_eq!(ring.slice_at_point(&slices, 20.0, 100.0), None);
+    }
+
+    #[test]
+    fn small_items_are_grouped() {
+        let grouping = group_small(&[90.0, 5.0, 0.5, 0.4, 0.1], 0.01, 0);
+        assert_eq!(grouping.shown, vec![0, 1]);
+        assert_eq!(grouping.other, vec![2, 3, 4]);
+        assert_eq!(grouping.depth, 0);
+    }
+
+    #[test]
+    fn a_single_small_item_is_not_grouped() {
+        let grouping = group_small(&[99.5, 0.5], 0.01, 0);
+        assert_eq!(grouping.shown, vec![0, 1]);
+        assert!(grouping.other.is_empty());
+    }
+
+    #[test]
+    fn expanding_lays_out_the_grouped_items() {
+        let grouping = group_small(&[1000.0, 5.0, 4.0, 0.01, 0.01], 0.01, 1);
+        assert_eq!(grouping.shown, vec![1, 2]);
+        assert_eq!(grouping.other, vec![3, 4]);
+        assert_eq!(grouping.depth, 1);
+    }
+
+    #[test]
+    fn expanding_stops_when_nothing_is_left_to_expand() {
+        let grouping = group_small(&[90.0, 5.0, 0.5, 0.4], 0.01, 3);
+        assert_eq!(grouping.shown, vec![2, 3]);
+        assert!(grouping.other.is_empty());
+        assert_eq!(grouping.depth, 1);
+    }
+
+    #[test]
+    fn zero_weights_are_not_grouped() {
+        let grouping = group_small(&[0.0, 0.0, 0.0], 0.01, 0);
+        assert_eq!(grouping.shown, vec![0, 1, 2]);
+        assert!(grouping.other.is_empty());
+    }
+
+    #[test]
+    fn colors_cycle_through_the_palette() {
+        assert_eq!(
+            slice_color(&COLORS, 0, false),
+            slice_color(&COLORS, 3, false)
+        );
+        assert_ne!(
+            slice_color(&COLORS, 0, false),
+            slice_color(&COLORS, 1, false)
+        );
+    }
+
+    #[test]
+    fn highlighted_colors_are_darker() {
+        let color = slice_color(&COLORS, 2, false);
+        let highlighted = slice_color(&COLORS, 2, true);
+        assert!(highlighted.red < color.red);
+        assert!(highlighted.green < color.green);
+        assert!(highlighted.blue < color.blue);
+        assert_eq!(highlighted.alpha, color.alpha);
+    }
+
+    #[test]
+    fn colors_are_parsed() {
+        let color = Color::parse("#ff8000").unwrap();
+        assert_eq!(color.red, 1.0);
+        assert!((color.green - 128.0 / 255.0).abs() < EPSILON);
+        assert_eq!(color.blue, 0.0);
+        assert_eq!(Color::parse("ff8000"), None);
+        assert_eq!(Color::parse("#ff80"), None);
+        assert_eq!(Color::parse("#gg8000"), None);
+    }
+
+    /// Draws a ring of `weights` on a transparent `size` by `size` image.
+    fn render(weights: &[f64], exceeded: &[usize], size: i32) -> cairo::ImageSurface {
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, size, size).unwrap();
+        {
+            let context = cairo::Context::new(&surface).unwrap();
+            let slices: Vec<RingSlice> = slice_angles(weights)
+                .into_iter()
+                .enumerate()
+                .map(|(index, angles)| RingSlice {
+                    angles,
+                    color: slice_color(&COLORS, index, false),
+                    exceeded: exceeded.contains(&index),
+                })
+                .collect();
+            draw_ring(&context, Ring::fit(size.into(), size.into()), &slices);
+        }
+        surface.flush();
+        surface
+    }
+
+    /// Color at `x`, `y` as premultiplied ARGB.
+    fn pixel(surface: &mut cairo::ImageSurface, x: usize, y: usize) -> [u8; 4] {
+        let stride = surface.stride() as usize;
+        let data = surface.data().unwrap();
+        let value = u32::from_ne_bytes(data[y * stride + x * 4..][..4].try_into().unwrap());
+        value.to_be_bytes()
+    }
+
+    /// Compares `surface` to the image `name` in `tests/golden`, allowing
+    /// for small anti-aliasing differences between cairo versions. The
+    /// images are written instead when `UPDATE_GOLDEN` is set.
+    fn assert_matches_golden(mut surface: cairo::ImageSurface, name: &str) {
+        let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+            .iter()
+            .collect::<PathBuf>()
+            .with_extension("png");
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            surface
+                .write_to_png(&mut File::create(&path).unwrap())
+                .unwrap();
+            return;
+        }
+
+        let mut file = File::open(&path).unwrap_or_else(|err| {
+            panic!(
+                "missing golden image {}, run with UPDATE_GOLDEN=1 to create it: {err}",
+                path.display()
+            )
+        });
+        let mut golden =
+            cairo::ImageSurface::create_from_png(&mut file).expect("failed to read golden image");
+        assert_eq!(
+            (golden.width(), golden.height()),
+            (surface.width(), surface.height()),
+            "{name} changed size"
+        );
+
+        let (width, height) = (surface.width() as usize, surface.height() as usize);
+        let mut differing = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let actual = pixel(&mut surface, x, y);
+                let expected = pixel(&mut golden, x, y);
+                if actual
+                    .iter()
+                    .zip(expected)
+                    .any(|(actual, expected)| actual.abs_diff(expected) > 8)
+                {
+                    differing += 1;
+                }
+            }
+        }
+        // edges may be anti-aliased differently
+        assert!(
+            differing * 100 <= width * height,
+            "{name} differs from {} in {differing} pixels",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn ring_is_drawn_with_gaps_and_a_hole() {
+        let mut surface = render(&[1.0, 1.0, 1.0, 1.0], &[], 100);
+
+        // the hole and the corners stay transparent
+        assert_eq!(pixel(&mut surface, 50, 50)[0], 0);
+        assert_eq!(pixel(&mut surface, 1, 1)[0], 0);
+        // the middle of the first slice, at 45°, is opaque
+        assert_eq!(pixel(&mut surface, 50 + 28, 50 + 28)[0], 255);
+
+        assert_matches_golden(surface, "ring");
+    }
+
+    #[test]
+    fn exceeded_slices_are_outlined() {
+        let surface = render(&[3.0, 1.0], &[1], 100);
+        assert_matches_golden(surface, "ring_exceeded");
+    }
+
+    #[test]
+    fn thin_slices_are_drawn_without_wrapping() {
+        let surface = render(&[1000.0, 0.001, 1.0], &[], 100);
+        assert_matches_golden(surface, "ring_thin_slices");
+    }
+}
@@ -0,0 +1,441 @@
+/* preferences.rs
+ *
+ * Copyright 2023 zd4y
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use crate::alias;
+use crate::app_goal::{self, AppGoal};
+use crate::category::{self, CategoryRule};
+use crate::config::APP_ID;
+use crate::date_preset::DatePreset;
+use crate::duration_format::DurationFormat;
+use crate::pie_chart::Palette;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Debug, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/zd4y/HyprlandAppTimer/ui/preferences.ui")]
+    pub struct HyprlandAppTimerGuiPreferencesWindow {
+        // Template widgets
+        #[template_child]
+        pub default_range_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub duration_format_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub min_slice_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub refresh_interval_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub palette_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub excluded_apps_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub excluded_app_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub aliases_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub alias_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub app_goals_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub app_goal_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub categories_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub category_entry: TemplateChild<adw::EntryRow>,
+
+        excluded_app_rows: RefCell<Vec<adw::ActionRow>>,
+        alias_rows: RefCell<Vec<adw::ActionRow>>,
+        app_goal_rows: RefCell<Vec<adw::ActionRow>>,
+        category_rows: RefCell<Vec<adw::ActionRow>>,
+        pub(super) settings: gio::Settings,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HyprlandAppTimerGuiPreferencesWindow {
+        const NAME: &'static str = "HyprlandAppTimerGuiPreferencesWindow";
+        type Type = super::HyprlandAppTimerGuiPreferencesWindow;
+        type ParentType = adw::PreferencesWindow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl HyprlandAppTimerGuiPreferencesWindow {
+        #[template_callback]
+        fn on_excluded_app_apply(&self) {
+            let app = self.excluded_app_entry.text().trim().to_string();
+            self.excluded_app_entry.set_text("");
+            if app.is_empty() {
+                return;
+            }
+
+            let mut excluded_apps: Vec<String> = self
+                .settings
+                .strv("excluded-apps")
+                .iter()
+                .map(|app| app.to_string())
+                .collect();
+            if excluded_apps.contains(&app) {
+                return;
+            }
+            excluded_apps.push(app);
+            self.settings
+                .set_strv("excluded-apps", excluded_apps)
+                .expect("failed to set excluded apps");
+        }
+
+        #[template_callback]
+        fn on_alias_apply(&self) {
+            let Some((class, app)) = alias::parse(&self.alias_entry.text()) else {
+                self.alias_entry.add_css_class("error");
+                return;
+            };
+            self.alias_entry.set_text("");
+
+            let mut aliases = alias::load(&self.settings);
+            aliases.insert(class, app);
+            alias::store(&self.settings, &aliases);
+        }
+
+        #[template_callback]
+        fn on_alias_changed(&self) {
+            self.alias_entry.remove_css_class("error");
+        }
+
+        #[template_callback]
+        fn on_app_goal_apply(&self) {
+            let Some((app, goal)) = AppGoal::parse(&self.app_goal_entry.text()) else {
+                self.app_goal_entry.add_css_class("error");
+                return;
+            };
+            self.app_goal_entry.set_text("");
+
+            let mut goals = app_goal::load(&self.settings);
+            goals.insert(app, goal);
+            app_goal::store(&self.settings, &goals);
+        }
+
+        #[template_callback]
+        fn on_app_goal_changed(&self) {
+            self.app_goal_entry.remove_css_class("error");
+        }
+
+        #[template_callback]
+        fn on_category_apply(&self) {
+            let Some(rule) = CategoryRule::parse(&self.category_entry.text()) else {
+                self.category_entry.add_css_class("error");
+                return;
+            };
+            self.category_entry.set_text("");
+
+            let mut rules = category::load(&self.settings);
+            rules.retain(|existing| existing.pattern != rule.pattern);
+            rules.push(rule);
+            category::store(&self.settings, &rules);
+        }
+
+        #[template_callback]
+        fn on_category_changed(&self) {
+            self.category_entry.remove_css_class("error");
+        }
+    }
+
+    impl ObjectImpl for HyprlandAppTimerGuiPreferencesWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            bind_choice(
+                &self.settings,
+                "default-range-preset",
+                &self.default_range_row,
+                DatePreset::ALL
+                    .into_iter()
+                    .filter(|preset| *preset != DatePreset::Custom)
+                    .map(DatePreset::nick)
+                    .collect(),
+            );
+            bind_choice(
+                &self.settings,
+                "duration-format",
+                &self.duration_format_row,
+                DurationFormat::ALL.map(DurationFormat::nick).to_vec(),
+            );
+            bind_choice(
+                &self.settings,
+                "color-palette",
+                &self.palette_row,
+                Palette::ALL.map(Palette::nick).to_vec(),
+            );
+            self.settings
+                .bind("min-slice-percentage", &*self.min_slice_row, "value")
+                .build();
+            self.settings
+                .bind("refresh-interval", &*self.refresh_interval_row, "value")
+                .build();
+
+            self.settings.connect_changed(
+                Some("excluded-apps"),
+                glib::clone!(@weak self as this => move |_, _| {
+                    this.update_excluded_app_rows();
+                }),
+            );
+            self.update_excluded_app_rows();
+
+            self.settings.connect_changed(
+                Some("app-aliases"),
+                glib::clone!(@weak self as this => move |_, _| {
+                    this.update_alias_rows();
+                }),
+            );
+            self.update_alias_rows();
+
+            for key in ["app-goals", "duration-format"] {
+                self.settings.connect_changed(
+                    Some(key),
+                    glib::clone!(@weak self as this => move |_, _| {
+                        this.update_app_goal_rows();
+                    }),
+                );
+            }
+            self.update_app_goal_rows();
+
+            self.settings.connect_changed(
+                Some("app-categories"),
+                glib::clone!(@weak self as this => move |_, _| {
+                    this.update_category_rows();
+                }),
+            );
+            self.update_category_rows();
+        }
+    }
+
+    impl HyprlandAppTimerGuiPreferencesWindow {
+        fn update_excluded_app_rows(&self) {
+            for row in self.excluded_app_rows.take() {
+                self.excluded_apps_group.remove(&row);
+            }
+
+            let mut rows = Vec::new();
+            for app in self.settings.strv("excluded-apps").iter() {
+                let app = app.to_string();
+                let row = adw::ActionRow::builder().title(&app).build();
+                let remove_button = gtk::Button::builder()
+                    .icon_name("list-remove-symbolic")
+                    .tooltip_text(gettextrs::gettext("Remove"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove_button.connect_clicked(glib::clone!(@weak self as this => move |_| {
+                    let excluded_apps: Vec<String> = this
+                        .settings
+                        .strv("excluded-apps")
+                        .iter()
+                        .map(|excluded| excluded.to_string())
+                        .filter(|excluded| *excluded != app)
+                        .collect();
+                    this.settings
+                        .set_strv("excluded-apps", excluded_apps)
+                        .expect("failed to set excluded apps");
+                }));
+                row.add_suffix(&remove_button);
+                self.excluded_apps_group.add(&row);
+                rows.push(row);
+            }
+            self.excluded_app_rows.replace(rows);
+        }
+
+        fn update_alias_rows(&self) {
+            for row in self.alias_rows.take() {
+                self.aliases_group.remove(&row);
+            }
+
+            let mut aliases: Vec<_> = alias::load(&self.settings).into_iter().collect();
+            aliases.sort();
+
+            let mut rows = Vec::new();
+            for (class, app) in aliases {
+                let row = adw::ActionRow::builder()
+                    .title(&class)
+                    .subtitle(&app)
+                    .build();
+                let remove_button = gtk::Button::builder()
+                    .icon_name("list-remove-symbolic")
+                    .tooltip_text(gettextrs::gettext("Remove"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove_button.connect_clicked(glib::clone!(@weak self as this => move |_| {
+                    let mut aliases = alias::load(&this.settings);
+                    aliases.remove(&class);
+                    alias::store(&this.settings, &aliases);
+                }));
+                row.add_suffix(&remove_button);
+                self.aliases_group.add(&row);
+                rows.push(row);
+            }
+            self.alias_rows.replace(rows);
+        }
+
+        fn update_app_goal_rows(&self) {
+            for row in self.app_goal_rows.take() {
+                self.app_goals_group.remove(&row);
+            }
+
+            let mut goals: Vec<_> = app_goal::load(&self.settings).into_iter().collect();
+            goals.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            let duration_format =
+                DurationFormat::from_nick(&self.settings.string("duration-format"));
+            let mut rows = Vec::new();
+            for (app, goal) in goals {
+                let row = adw::ActionRow::builder()
+                    .title(&app)
+                    .subtitle(goal.describe(duration_format))
+                    .build();
+                let remove_button = gtk::Button::builder()
+                    .icon_name("list-remove-symbolic")
+                    .tooltip_text(gettextrs::gettext("Remove"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove_button.connect_clicked(glib::clone!(@weak self as this => move |_| {
+                    let mut goals = app_goal::load(&this.settings);
+                    goals.remove(&app);
+                    app_goal::store(&this.settings, &goals);
+                }));
+                row.add_suffix(&remove_button);
+                self.app_goals_group.add(&row);
+                rows.push(row);
+            }
+            self.app_goal_rows.replace(rows);
+        }
+
+        fn update_category_rows(&self) {
+            for row in self.category_rows.take() {
+                self.categories_group.remove(&row);
+            }
+
+            let mut rows = Vec::new();
+            for rule in category::load(&self.settings) {
+                let row = adw::ActionRow::builder()
+                    .title(&rule.pattern)
+                    .subtitle(&rule.category)
+                    .build();
+                let remove_button = gtk::Button::builder()
+                    .icon_name("list-remove-symbolic")
+                    .tooltip_text(gettextrs::gettext("Remove"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove_button.connect_clicked(glib::clone!(@weak self as this => move |_| {
+                    let mut rules = category::load(&this.settings);
+                    rules.retain(|existing| *existing != rule);
+                    category::store(&this.settings, &rules);
+                }));
+                row.add_suffix(&remove_button);
+                self.categories_group.add(&row);
+                rows.push(row);
+            }
+            self.category_rows.replace(rows);
+        }
+    }
+
+    impl WidgetImpl for HyprlandAppTimerGuiPreferencesWindow {}
+    impl WindowImpl for HyprlandAppTimerGuiPreferencesWindow {}
+    impl AdwWindowImpl for HyprlandAppTimerGuiPreferencesWindow {}
+    impl PreferencesWindowImpl for HyprlandAppTimerGuiPreferencesWindow {}
+
+    impl Default for HyprlandAppTimerGuiPreferencesWindow {
+        fn default() -> Self {
+            HyprlandAppTimerGuiPreferencesWindow {
+                default_range_row: Default::default(),
+                duration_format_row: Default::default(),
+                min_slice_row: Default::default(),
+                refresh_interval_row: Default::default(),
+                palette_row: Default::default(),
+                excluded_apps_group: Default::default(),
+                excluded_app_entry: Default::default(),
+                aliases_group: Default::default(),
+                alias_entry: Default::default(),
+                alias_rows: Default::default(),
+                app_goals_group: Default::default(),
+                app_goal_entry: Default::default(),
+                excluded_app_rows: Default::default(),
+                app_goal_rows: Default::default(),
+                categories_group: Default::default(),
+                category_entry: Default::default(),
+                category_rows: Default::default(),
+                settings: gio::Settings::new(APP_ID),
+            }
+        }
+    }
+
+    /// Binds a string enum setting to a combo row whose items are in the same
+    /// order as `nicks`.
+    fn bind_choice(
+        settings: &gio::Settings,
+        key: &str,
+        row: &adw::ComboRow,
+        nicks: Vec<&'static str>,
+    ) {
+        let set_nicks = nicks.clone();
+        settings
+            .bind(key, row, "selected")
+            .mapping(move |variant, _| {
+                let nick = variant.str()?;
+                let index = nicks.iter().position(|n| *n == nick)?;
+                Some((index as u32).to_value())
+            })
+            .set_mapping(move |value, _| {
+                let index = value.get::<u32>().ok()?;
+                set_nicks.get(index as usize).map(|nick| nick.to_variant())
+            })
+            .build();
+    }
+}
+
+glib::wrapper! {
+    pub struct HyprlandAppTimerGuiPreferencesWindow(ObjectSubclass<imp::HyprlandAppTimerGuiPreferencesWindow>)
+        @extends gtk::Widget, gtk::Window, adw::Window, adw::PreferencesWindow,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl HyprlandAppTimerGuiPreferencesWindow {
+    /// Creates the window, modal to `parent` when there is one.
+    pub fn new(parent: Option<&gtk::Window>) -> Self {
+        glib::Object::builder()
+            .property("transient-for", parent)
+            .property("modal", parent.is_some())
+            .build()
+    }
+}
@@ -0,0 +1,79 @@
+use std::{cmp::Ordering, time::Duration};
+
+/// Order of the entries in the usage list, see the `sort-mode` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    /// Longest first.
+    #[default]
+    Duration,
+    /// Alphabetically, ignoring case.
+    Name,
+    /// Largest increase from the previous period first.
+    Change,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 3] = [SortMode::Duration, SortMode::Name, SortMode::Change];
+
+    pub fn from_nick(nick: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.nick() == nick)
+            .unwrap_or_default()
+    }
+
+    pub fn nick(self) -> &'static str {
+        match self {
+            SortMode::Duration => "duration",
+            SortMode::Name => "name",
+            SortMode::Change => "change",
+        }
+    }
+
+    /// Compares two entries given the name, duration and previous period's
+    /// duration of each, breaking ties by duration.
+    pub fn compare(self, a: (&str, Duration, Duration), b: (&str, Duration, Duration)) -> Ordering {
+        self.key(a).cmp(&self.key(b))
+    }
+
+    fn key(self, (name, duration, previous): (&str, Duration, Duration)) -> SortKey {
+        SortKey {
+            mode: self,
+            name: name.to_lowercase(),
+            duration,
+            change: duration.as_secs_f64() - previous.as_secs_f64(),
+        }
+    }
+}
+
+struct SortKey {
+    mode: SortMode,
+    name: String,
+    duration: Duration,
+    change: f64,
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = match self.mode {
+            SortMode::Duration => Ordering::Equal,
+            SortMode::Name => self.name.cmp(&other.name),
+            SortMode::Change => other.change.total_cmp(&self.change),
+        };
+        ordering.then_with(|| other.duration.cmp(&self.duration))
+    }
+}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortKey {}
@@ -0,0 +1,216 @@
+use adw::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{cairo, glib, pango};
+
+use std::time::Duration;
+
+use crate::duration_format::DurationFormat;
+use crate::pie_chart::{item_color, Palette};
+
+/// Usage of a single app in each hour of the day.
+#[derive(Debug, Clone)]
+pub struct TimelineLane {
+    pub title: String,
+    pub seconds: [f64; 24],
+}
+
+impl TimelineLane {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            seconds: [0.0; 24],
+        }
+    }
+}
+
+mod imp {
+    const LANE_HEIGHT: f64 = 28.0;
+    const LANE_SPACING: f64 = 6.0;
+    const HEADER_HEIGHT: f64 = 24.0;
+    const MARGIN: f64 = 12.0;
+    const MAX_LABEL_WIDTH: f64 = 180.0;
+
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    #[derive(Debug, Default, glib::Properties)]
+    #[properties(wrapper_type = super::Timeline)]
+    pub struct Timeline {
+        pub(super) lanes: RefCell<Vec<TimelineLane>>,
+        label_width: Cell<f64>,
+        width: Cell<f64>,
+        pub(super) duration_format: Cell<DurationFormat>,
+
+        #[property(get, set = Self::set_palette, builder(Palette::default()))]
+        palette: Cell<Palette>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for Timeline {
+        const NAME: &'static str = "Timeline";
+        type Type = super::Timeline;
+        type ParentType = gtk::DrawingArea;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for Timeline {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_draw_func(glib::clone!(@weak self as widget => move |_, cr, w, h| {
+                widget.draw_timeline(cr, w, h);
+            }));
+
+            let motion_controller = gtk::EventControllerMotion::new();
+            motion_controller.connect_motion(glib::clone!(@weak self as widget => move |_, x, y| {
+                widget.update_tooltip(x, y);
+            }));
+            obj.add_controller(motion_controller);
+        }
+    }
+    impl WidgetImpl for Timeline {}
+    impl DrawingAreaImpl for Timeline {}
+
+    impl Timeline {
+        fn set_palette(&self, palette: Palette) {
+            self.palette.set(palette);
+            self.obj().queue_draw();
+        }
+
+        fn draw_timeline(&self, context: &cairo::Context, width: i32, _height: i32) {
+            let obj = self.obj();
+            let width: f64 = width.into();
+            let lanes = self.lanes.borrow();
+
+            let label_width = lanes
+                .iter()
+                .map(|lane| {
+                    let (_, logical) = obj.create_pango_layout(Some(&lane.title)).pixel_extents();
+                    logical.width() as f64
+                })
+                .fold(0.0, f64::max)
+                .min(MAX_LABEL_WIDTH);
+            self.label_width.set(label_width);
+            self.width.set(width);
+
+            let hour_width = self.hour_width();
+            let x0 = MARGIN + label_width + MARGIN;
+
+            // hour labels and grid
+            for hour in (0..=24).step_by(3) {
+                let x = x0 + hour as f64 * hour_width;
+                context.set_source_rgba(0.5, 0.5, 0.5, 0.3);
+                context.rectangle(x, HEADER_HEIGHT, 1.0, self.content_height() - HEADER_HEIGHT);
+                context.fill().expect("failed to fill");
+
+                if hour == 24 {
+                    continue;
+                }
+                let layout = obj.create_pango_layout(Some(&format!("{hour:02}:00")));
+                let (_, logical) = layout.pixel_extents();
+                context.set_source_rgba(0.5, 0.5, 0.5, 1.0);
+                context.move_to(x + 2.0, (HEADER_HEIGHT - logical.height() as f64) / 2.0);
+                pangocairo::functions::show_layout(context, &layout);
+            }
+
+            for (index, lane) in lanes.iter().enumerate() {
+                let y = HEADER_HEIGHT + index as f64 * (LANE_HEIGHT + LANE_SPACING);
+
+                let layout = obj.create_pango_layout(Some(&lane.title));
+                layout.set_width(pango::units_from_double(label_width));
+                layout.set_ellipsize(pango::EllipsizeMode::End);
+                let (_, logical) = layout.pixel_extents();
+                GdkCairoContextExt::set_source_rgba(context, &obj.color());
+                context.move_to(MARGIN, y + (LANE_HEIGHT - logical.height() as f64) / 2.0);
+                pangocairo::functions::show_layout(context, &layout);
+
+                GdkCairoContextExt::set_source_rgba(
+                    context,
+                    &item_color(self.palette.get(), index, false),
+                );
+                for (hour, seconds) in lane.seconds.iter().enumerate() {
+                    if *seconds <= 0.0 {
+                        continue;
+                    }
+                    let block_width = (seconds / 3600.0).min(1.0) * hour_width;
+                    context.rectangle(
+                        x0 + hour as f64 * hour_width,
+                        y,
+                        block_width.max(1.0),
+                        LANE_HEIGHT,
+                    );
+                }
+                context.fill().expect("failed to fill");
+            }
+        }
+
+        fn update_tooltip(&self, x: f64, y: f64) {
+            let obj = self.obj();
+            match self.block_at_point(x, y) {
+                Some((lane, hour, seconds)) => {
+                    let duration = self
+                        .duration_format
+                        .get()
+                        .format(Duration::from_secs_f64(seconds));
+                    obj.set_tooltip_text(Some(&format!(
+                        "{lane}\n{hour:02}:00 – {:02}:00: {duration}",
+                        hour + 1
+                    )));
+                    obj.set_has_tooltip(true);
+                }
+                None => {
+                    obj.set_tooltip_text(None);
+                    obj.set_has_tooltip(false);
+                }
+            }
+        }
+
+        /// Returns the lane title, hour and seconds of the block under the point.
+        fn block_at_point(&self, x: f64, y: f64) -> Option<(String, usize, f64)> {
+            let x0 = MARGIN + self.label_width.get() + MARGIN;
+            if x < x0 || y < HEADER_HEIGHT {
+                return None;
+            }
+
+            let hour = ((x - x0) / self.hour_width()) as usize;
+            let lane_index = ((y - HEADER_HEIGHT) / (LANE_HEIGHT + LANE_SPACING)) as usize;
+            let lanes = self.lanes.borrow();
+            let lane = lanes.get(lane_index)?;
+            let seconds = *lane.seconds.get(hour)?;
+
+            (seconds > 0.0).then(|| (lane.title.clone(), hour, seconds))
+        }
+
+        fn hour_width(&self) -> f64 {
+            let x0 = MARGIN + self.label_width.get() + MARGIN;
+            ((self.width.get() - x0 - MARGIN) / 24.0).max(1.0)
+        }
+
+        pub(super) fn content_height(&self) -> f64 {
+            let lanes = self.lanes.borrow().len() as f64;
+            HEADER_HEIGHT + lanes * (LANE_HEIGHT + LANE_SPACING)
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct Timeline(ObjectSubclass<imp::Timeline>)
+        @extends gtk::Widget, gtk::DrawingArea;
+}
+
+impl Timeline {
+    pub fn set_lanes(&self, lanes: Vec<TimelineLane>) {
+        let imp = self.imp();
+        {
+            *imp.lanes.borrow_mut() = lanes;
+        }
+        self.set_content_height(imp.content_height() as i32);
+        self.queue_draw();
+    }
+
+    /// Sets how the durations in the tooltips are displayed.
+    pub fn set_duration_format(&self, duration_format: DurationFormat) {
+        self.imp().duration_format.set(duration_format);
+    }
+}
@@ -0,0 +1,109 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{Local, NaiveDate, TimeZone, Utc};
+use gtk::gio;
+use gtk::prelude::*;
+use hyprland_app_timer::{AppUsage, Client, SqliteDB};
+
+use crate::alias;
+
+/// Settings applied to all the usage read from the database.
+#[derive(Debug, Clone, Default)]
+pub struct UsageFilter {
+    pub excluded_apps: Vec<String>,
+    /// Whether the excluded apps are kept anyway.
+    pub show_hidden: bool,
+    /// Maps window classes to the app their usage is merged into.
+    pub aliases: HashMap<String, String>,
+}
+
+impl UsageFilter {
+    pub fn from_settings(settings: &gio::Settings) -> Self {
+        Self {
+            excluded_apps: settings
+                .strv("excluded-apps")
+                .iter()
+                .map(|app| app.to_string())
+                .collect(),
+            show_hidden: settings.boolean("show-hidden-apps"),
+            aliases: alias::load(settings),
+        }
+    }
+
+    /// Whether `class`, or the app it is an alias of, is excluded.
+    pub fn is_hidden(&self, class: &str) -> bool {
+        self.excluded_apps.iter().any(|app| app == class)
+            || self
+                .aliases
+                .get(class)
+                .is_some_and(|app| self.excluded_apps.contains(app))
+    }
+
+    /// Total usage of the excluded apps, shown or not.
+    pub fn hidden_duration(&self, apps_usage: &[AppUsage]) -> Duration {
+        apps_usage
+            .iter()
+            .filter(|app_usage| self.is_hidden(&app_usage.app))
+            .map(|app_usage| app_usage.duration)
+            .sum()
+    }
+
+    /// Merges aliased apps and drops the excluded ones unless shown, keeping
+    /// the longest usage first.
+    pub fn apply(&self, apps_usage: Vec<AppUsage>) -> Vec<AppUsage> {
+        let mut merged: Vec<AppUsage> = Vec::with_capacity(apps_usage.len());
+        for mut app_usage in apps_usage {
+            if !self.show_hidden && self.is_hidden(&app_usage.app) {
+                continue;
+            }
+            if let Some(app) = self.aliases.get(&app_usage.app) {
+                app_usage.app = app.clone();
+            }
+
+            match merged.iter_mut().find(|merged| merged.app == app_usage.app) {
+                Some(merged) => merged.duration += app_usage.duration,
+                None => merged.push(app_usage),
+            }
+        }
+        merged.sort_by(|a, b| b.duration.cmp(&a.duration));
+        merged
+    }
+}
+
+/// Asks the daemon to write its in-memory usage to the database.
+pub async fn save() -> Result<(), String> {
+    Client::new()
+        .await
+        .map_err(|err| err.to_string())?
+        .save()
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Fetches the usage of the whole `day`.
+pub async fn fetch_day(db: &SqliteDB, day: NaiveDate) -> Result<Vec<AppUsage>, String> {
+    db.get_apps_usage(
+        date_naive_to_utc(day),
+        date_naive_to_utc(day.succ_opt().unwrap()),
+    )
+    .await
+    .map_err(|err| err.to_string())
+}
+
+/// Start of the local `date` in UTC, as stored in the database.
+pub fn date_naive_to_utc(date: NaiveDate) -> chrono::DateTime<Utc> {
+    local_hour_to_utc(date, 0)
+}
+
+/// Start of the local `hour` (0 to 24) of `date` in UTC. An hour skipped
+/// when clocks move forward starts with the next one, and an hour repeated
+/// when they move back starts the first time.
+pub fn local_hour_to_utc(date: NaiveDate, hour: u32) -> chrono::DateTime<Utc> {
+    let mut local = date.and_hms_opt(0, 0, 0).unwrap() + chrono::Duration::hours(hour.into());
+    loop {
+        if let Some(time) = Local.from_local_datetime(&local).earliest() {
+            return time.with_timezone(&Utc);
+        }
+        local += chrono::Duration::hours(1);
+    }
+}
@@ -20,39 +20,153 @@
 
 use adw::subclass::prelude::*;
 use gtk::prelude::*;
-use gtk::{gio, glib};
+use gtk::{gdk, gio, glib};
 
 mod imp {
-    use std::{cell::RefCell, sync::Arc, time::Duration};
+    use std::{
+        cell::{Cell, RefCell},
+        sync::Arc,
+        time::Duration,
+    };
 
-    use chrono::{Days, Local, NaiveDate, TimeZone, Utc};
+    use chrono::{Datelike, Local, NaiveDate};
+    use gettextrs::gettext;
     use gtk::glib::{Receiver, Sender};
-    use hyprland_app_timer::{AppUsage, Client, SqliteDB};
-    use tokio::runtime::Runtime;
+    use hyprland_app_timer::{AppUsage, SqliteDB};
+    use tokio::sync::OnceCell;
 
+    use crate::app_goal::{self, AppGoal, AppGoalKind};
+    use crate::app_info::{self, AppDisplayInfo};
+    use crate::app_usage_object::{self, AppUsageObject};
+    use crate::application::HyprlandAppTimerGuiApplication;
+    use crate::bar_chart::{BarChart, BarChartBar, BarChartSegment};
+    use crate::category;
+    use crate::config::APP_ID;
+    use crate::date_preset::DatePreset;
+    use crate::duration_format::DurationFormat;
+    use crate::export::{self, ChartFormat, ExportFormat};
+    use crate::heatmap::Heatmap;
     use crate::pie_chart::{PieChart, PieChartItem};
+    use crate::sort_mode::SortMode;
+    use crate::timeline::{Timeline, TimelineLane};
+    use crate::usage::{self, date_naive_to_utc, UsageFilter};
 
     use super::*;
 
+    /// Number of days shown in the details page.
+    const HISTORY_DAYS: i64 = 30;
+    /// Size of the saved charts, in pixels or points.
+    const CHART_WIDTH: f64 = 1200.0;
+    const CHART_HEIGHT: f64 = 800.0;
+    /// How far back the latest day with usage is looked for.
+    const LATEST_ACTIVITY_DAYS: i64 = 366;
+    /// Number of weeks shown in the calendar heatmap.
+    const HEATMAP_WEEKS: i64 = 53;
+    /// How many days of the calendar heatmap are queried at once.
+    const HEATMAP_CONCURRENT_QUERIES: usize = 8;
+
     #[derive(Debug, gtk::CompositeTemplate)]
     #[template(resource = "/io/github/zd4y/HyprlandAppTimer/ui/window.ui")]
     pub struct HyprlandAppTimerGuiWindow {
         // Template widgets
         #[template_child]
+        pub date_menu_button: TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        pub preset_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
         pub calendar_date_start: TemplateChild<gtk::Calendar>,
         #[template_child]
         pub calendar_date_end: TemplateChild<gtk::Calendar>,
         #[template_child]
         pub date_range_checkbox: TemplateChild<gtk::CheckButton>,
         #[template_child]
-        pub listbox: TemplateChild<gtk::ListBox>,
+        pub category_toggle: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub navigation_view: TemplateChild<adw::NavigationView>,
+        #[template_child]
+        pub details_page: TemplateChild<adw::NavigationPage>,
+        #[template_child]
+        pub details_duration_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub details_share_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub details_bar_chart: TemplateChild<BarChart>,
+        #[template_child]
+        pub details_history_total_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub details_average_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub details_longest_day_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub details_history_share_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub main_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub error_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub search_bar: TemplateChild<gtk::SearchBar>,
+        #[template_child]
+        pub search_entry: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
+        pub column_view: TemplateChild<gtk::ColumnView>,
+        #[template_child]
+        pub hidden_bar: TemplateChild<gtk::ActionBar>,
+        #[template_child]
+        pub hidden_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub show_hidden_toggle: TemplateChild<gtk::ToggleButton>,
         #[template_child]
         pub pie_chart: TemplateChild<PieChart>,
+        #[template_child]
+        pub timeline: TemplateChild<Timeline>,
+        #[template_child]
+        pub timeline_page: TemplateChild<adw::ViewStackPage>,
+        #[template_child]
+        pub bar_chart: TemplateChild<BarChart>,
+        #[template_child]
+        pub bar_chart_page: TemplateChild<adw::ViewStackPage>,
+        #[template_child]
+        pub heatmap_title: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub heatmap: TemplateChild<Heatmap>,
 
+        /// Set while the range is changed programmatically so the widget
+        /// signals don't each trigger a reload.
+        updating_range: Cell<bool>,
+        /// Range and usage currently displayed, kept for exporting.
+        loaded_range: Cell<Option<(NaiveDate, NaiveDate)>>,
+        /// Incremented by every load of the usage so the results of an older
+        /// load, finishing late, are dropped.
+        load_generation: Cell<u64>,
+        apps_usage: RefCell<Vec<AppUsage>>,
+        /// Usage of the period of the same length just before.
+        previous_usage: RefCell<Vec<AppUsage>>,
+        /// How much of the loaded usage belongs to hidden apps.
+        hidden_usage: Cell<Duration>,
+        /// Apps or categories listed in the column view.
+        usage_store: gio::ListStore,
+        usage_selection: gtk::SingleSelection,
+        search_filter: gtk::CustomFilter,
+        /// Sorts by the `sort-mode` setting when no column is sorted.
+        sort_mode_sorter: gtk::CustomSorter,
+        sort_mode: Cell<SortMode>,
+        /// Year and month whose days with usage are marked in each calendar.
+        start_marked_month: Cell<Option<(i32, i32)>>,
+        end_marked_month: Cell<Option<(i32, i32)>>,
+        /// Usage of every day shown in the calendar heatmap, filtered when
+        /// shown so changing the filter doesn't fetch it again.
+        heatmap_days: RefCell<Vec<(NaiveDate, Vec<AppUsage>)>>,
+        /// App shown in the details page.
+        details_app: RefCell<Option<String>>,
+        /// Daily usage of the details app and the usage of every app over
+        /// those days, once loaded.
+        details_history: RefCell<Option<(Vec<(NaiveDate, Duration)>, Duration)>>,
+        pub(super) settings: gio::Settings,
         sender: Sender<Message>,
         receiver: RefCell<Option<Receiver<Message>>>,
-        rt: Runtime,
-        db: Arc<SqliteDB>,
+        refresh_source: RefCell<Option<glib::SourceId>>,
     }
 
     #[glib::object_subclass]
@@ -81,66 +195,124 @@ mod imp {
 
         #[template_callback]
         fn on_date_change(&self) {
-            let date_start = date_glib_to_chrono(&self.calendar_date_start.date());
-
-            let date_end = if self.date_range_checkbox.is_active() {
-                let date_end = self
-                    .calendar_date_end
-                    .date()
-                    .add_days(1)
-                    .expect("failed to add days");
-                date_glib_to_chrono(&date_end)
-            } else {
-                date_start
-                    .checked_add_days(Days::new(1))
-                    .expect("failed to add days")
+            if self.updating_range.get() {
+                return;
+            }
+
+            self.sync_preset();
+            self.mark_days_with_usage();
+
+            let (start, end) = self.selected_range();
+            self.timeline_page.set_visible(start == end);
+            self.heatmap.set_selection(start, end);
+            self.bar_chart_page.set_visible(start != end);
+
+            let Some(application) = self.application() else {
+                return;
             };
+            let db = application.db();
+            let sender = self.sender.clone();
+            let filter = UsageFilter::from_settings(&self.settings);
+            let generation = self.next_load_generation();
+            application.spawn(load_apps_usage(db, sender, generation, start, end, filter));
+        }
+
+        #[template_callback]
+        fn on_preset_selected(&self) {
+            if self.updating_range.get() {
+                return;
+            }
+
+            let preset = DatePreset::from_index(self.preset_dropdown.selected());
+            match preset.range(Local::now().date_naive()) {
+                Some((start, end)) => self.set_date_range(start, end),
+                None => self.date_menu_button.popup(),
+            }
+        }
+
+        #[template_callback]
+        fn on_search_changed(&self) {
+            self.search_filter.changed(gtk::FilterChange::Different);
+            self.expand_search_matches();
+            let search_text = self.search_entry.text();
+            self.pie_chart
+                .set_search_text((!search_text.is_empty()).then(|| search_text.to_string()));
+        }
+
+        #[template_callback]
+        fn on_retry_clicked(&self) {
+            self.on_date_change();
+        }
 
-            let db = self.db.clone();
+        #[template_callback]
+        fn on_latest_activity_clicked(&self) {
+            let Some(application) = self.application() else {
+                return;
+            };
+            let db = application.db();
             let sender = self.sender.clone();
-            self.rt.spawn(async move {
-                let apps_usage = db
-                    .get_apps_usage(date_start, date_end)
-                    .await
-                    .expect("failed to get apps usage");
+            let filter = UsageFilter::from_settings(&self.settings);
+            let today = Local::now().date_naive();
+            application.spawn(async move {
+                let msg = match fetch_latest_day_with_usage(&db, today, &filter).await {
+                    Ok(day) => Message::LatestDayWithUsage(day),
+                    Err(err) => Message::Error(err),
+                };
                 sender
-                    .send(Message::AppsUsage(apps_usage))
-                    .expect("failed to send apps usage");
+                    .send(msg)
+                    .expect("failed to send latest day with usage");
             });
         }
+
+        #[template_callback]
+        fn on_previous_clicked(&self) {
+            self.shift_range(-1);
+        }
+
+        #[template_callback]
+        fn on_next_clicked(&self) {
+            self.shift_range(1);
+        }
     }
 
     impl ObjectImpl for HyprlandAppTimerGuiWindow {
         fn constructed(&self) {
             self.parent_constructed();
 
-            let initial_datetime = self.calendar_date_start.date();
+            self.setup_settings();
 
-            let db = self.db.clone();
-            let sender = self.sender.clone();
+            let preset = DatePreset::from_nick(&self.settings.string("default-range-preset"));
+            if let Some((start, end)) = preset.range(Local::now().date_naive()) {
+                self.select_range(start, end);
+            }
+            self.sync_preset();
+            let (start, end) = self.selected_range();
+            self.heatmap.set_selection(start, end);
 
-            self.rt.spawn(async move {
-                if let Err(err) = Client::new()
-                    .await
-                    .expect("failed to get client")
-                    .save()
-                    .await
-                {
-                    eprintln!("Error: failed to send save message: {err}")
-                }
+            self.obj().setup_gactions();
 
-                let date_start = date_glib_to_chrono(&initial_datetime);
-                let date_end = date_start.checked_add_days(Days::new(1)).unwrap();
+            self.pie_chart.connect_item_activated(
+                glib::clone!(@weak self as this => move |_, item| {
+                    let title = item.title();
+                    let row = this.select_row_by_title(&title, true);
+                    if this.is_grouped_by_category() {
+                        if let Some(row) = row {
+                            row.set_expanded(true);
+                        }
+                    } else {
+                        this.show_details(&title);
+                    }
+                }),
+            );
 
-                let apps_usage = db
-                    .get_apps_usage(date_start, date_end)
-                    .await
-                    .expect("failed to get apps usage");
+            self.heatmap
+                .connect_day_activated(glib::clone!(@weak self as this => move |_, day| {
+                    this.set_date_range(day, day);
+                }));
 
-                sender
-                    .send(Message::AppsUsage(apps_usage))
-                    .expect("failed to send apps usage");
-            });
+            self.search_bar.connect_entry(&*self.search_entry);
+            self.search_bar.set_key_capture_widget(Some(&*self.obj()));
+            self.setup_column_view();
 
             self.receiver.take().unwrap().attach(None, glib::clone!(@weak self as this => @default-return glib::ControlFlow::Continue, move |msg| {
                 this.handle_message(msg);
@@ -150,96 +322,1733 @@ mod imp {
     }
 
     impl HyprlandAppTimerGuiWindow {
-        fn handle_message(&self, msg: Message) {
-            match msg {
-                Message::AppsUsage(apps_usage) => {
-                    self.pie_chart.clear();
-                    while let Some(child) = self.listbox.last_child() {
-                        self.listbox.remove(&child);
+        /// Saves the usage of the daemon and loads everything shown. Called
+        /// once the window has its application, which is only set after
+        /// `constructed`.
+        pub(super) fn load_initial(&self) {
+            let Some(application) = self.application() else {
+                return;
+            };
+            let db = application.db();
+            let sender = self.sender.clone();
+            let filter = UsageFilter::from_settings(&self.settings);
+            let (start, end) = self.selected_range();
+            let generation = self.next_load_generation();
+
+            application.spawn(async move {
+                if let Err(err) = usage::save().await {
+                    sender
+                        .send(Message::SaveFailed(err))
+                        .expect("failed to send save error");
+                }
+
+                load_apps_usage(db, sender, generation, start, end, filter).await;
+            });
+            self.load_heatmap();
+            self.mark_days_with_usage();
+        }
+
+        /// Returns the first and last day (inclusive) currently selected.
+        fn selected_range(&self) -> (NaiveDate, NaiveDate) {
+            let start = date_glib_to_naive(&self.calendar_date_start.date());
+            if !self.date_range_checkbox.is_active() {
+                return (start, start);
+            }
+
+            let end = date_glib_to_naive(&self.calendar_date_end.date());
+            (start, end.max(start))
+        }
+
+        /// Selects the days from `start` to `end` (inclusive) and reloads once.
+        fn set_date_range(&self, start: NaiveDate, end: NaiveDate) {
+            self.select_range(start, end);
+            self.on_date_change();
+        }
+
+        /// Selects the days from `start` to `end` (inclusive) without reloading.
+        fn select_range(&self, start: NaiveDate, end: NaiveDate) {
+            self.updating_range.set(true);
+            self.calendar_date_start
+                .select_day(&date_naive_to_glib(start));
+            self.calendar_date_end.select_day(&date_naive_to_glib(end));
+            self.date_range_checkbox.set_active(start != end);
+            self.calendar_date_end.set_visible(start != end);
+            self.updating_range.set(false);
+        }
+
+        fn setup_settings(&self) {
+            let obj = self.obj();
+            obj.set_default_size(
+                self.settings.int("window-width"),
+                self.settings.int("window-height"),
+            );
+            if self.settings.boolean("window-maximized") {
+                obj.maximize();
+            }
+
+            self.settings
+                .bind("min-slice-percentage", &*self.pie_chart, "min-weight-ratio")
+                .mapping(|variant, _| {
+                    let percentage = variant.get::<f64>()?;
+                    Some((percentage / 100.0).to_value())
+                })
+                .get_only()
+                .build();
+            self.settings
+                .bind("color-palette", &*self.pie_chart, "palette")
+                .get_only()
+                .build();
+            self.settings
+                .bind("color-palette", &*self.timeline, "palette")
+                .get_only()
+                .build();
+            self.settings
+                .bind("color-palette", &*self.bar_chart, "palette")
+                .get_only()
+                .build();
+            self.settings
+                .bind("color-palette", &*self.heatmap, "palette")
+                .get_only()
+                .build();
+
+            self.settings.connect_changed(
+                Some("refresh-interval"),
+                glib::clone!(@weak self as this => move |_, _| {
+                    this.restart_refresh_timer();
+                }),
+            );
+            self.restart_refresh_timer();
+
+            self.settings
+                .bind("group-by-category", &*self.category_toggle, "active")
+                .build();
+            self.settings
+                .bind("show-hidden-apps", &*self.show_hidden_toggle, "active")
+                .build();
+            for key in ["group-by-category", "app-categories"] {
+                self.settings.connect_changed(
+                    Some(key),
+                    glib::clone!(@weak self as this => move |_, key| {
+                        // apps and categories may share titles
+                        if key == "group-by-category" {
+                            this.usage_store.remove_all();
+                        }
+                        this.pie_chart.clear();
+                        this.update_usage_views();
+                    }),
+                );
+            }
+
+            self.settings.connect_changed(
+                Some("sort-mode"),
+                glib::clone!(@weak self as this => move |settings, _| {
+                    this.sort_mode
+                        .set(SortMode::from_nick(&settings.string("sort-mode")));
+                    // the setting takes over from a sorted column
+                    this.column_view
+                        .sort_by_column(None::<&gtk::ColumnViewColumn>, gtk::SortType::Ascending);
+                    this.sort_mode_sorter.changed(gtk::SorterChange::Different);
+                }),
+            );
+            self.sort_mode
+                .set(SortMode::from_nick(&self.settings.string("sort-mode")));
+
+            self.settings.connect_changed(
+                Some("compare-previous-period"),
+                glib::clone!(@weak self as this => move |_, _| {
+                    this.update_usage_views();
+                }),
+            );
+
+            self.settings.connect_changed(
+                Some("duration-format"),
+                glib::clone!(@weak self as this => move |_, _| {
+                    this.update_duration_format();
+                }),
+            );
+            self.set_chart_duration_format();
+
+            for key in [
+                "excluded-apps",
+                "show-hidden-apps",
+                "app-aliases",
+                "app-goals",
+            ] {
+                self.settings.connect_changed(
+                    Some(key),
+                    glib::clone!(@weak self as this => move |_, _| {
+                        this.on_date_change();
+                    }),
+                );
+            }
+
+            for key in ["excluded-apps", "show-hidden-apps", "app-aliases"] {
+                self.settings.connect_changed(
+                    Some(key),
+                    glib::clone!(@weak self as this => move |_, _| {
+                        this.update_heatmap();
+                    }),
+                );
+            }
+            for key in ["excluded-apps", "show-hidden-apps", "app-aliases"] {
+                self.settings.connect_changed(
+                    Some(key),
+                    glib::clone!(@weak self as this => move |_, _| {
+                        this.start_marked_month.set(None);
+                        this.end_marked_month.set(None);
+                        this.mark_days_with_usage();
+                    }),
+                );
+            }
+        }
+
+        fn restart_refresh_timer(&self) {
+            if let Some(source) = self.refresh_source.take() {
+                source.remove();
+            }
+
+            let interval = self.settings.uint("refresh-interval");
+            if interval == 0 {
+                return;
+            }
+
+            let source = glib::timeout_add_seconds_local(
+                interval,
+                glib::clone!(@weak self as this => @default-return glib::ControlFlow::Break, move || {
+                    this.refresh();
+                    glib::ControlFlow::Continue
+                }),
+            );
+            self.refresh_source.replace(Some(source));
+        }
+
+        /// Saves and reloads the selected range if it includes today, so the
+        /// displayed usage keeps up while the window stays open.
+        fn refresh(&self) {
+            let (start, end) = self.selected_range();
+            let today = Local::now().date_naive();
+            if self.updating_range.get() || today < start || today > end {
+                return;
+            }
+
+            let Some(application) = self.application() else {
+                return;
+            };
+            let db = application.db();
+            let sender = self.sender.clone();
+            let filter = UsageFilter::from_settings(&self.settings);
+            let generation = self.next_load_generation();
+            application.spawn(async move {
+                if let Err(err) = usage::save().await {
+                    eprintln!("Error: failed to send save message: {err}");
+                }
+                load_apps_usage(db, sender, generation, start, end, filter).await;
+            });
+        }
+
+        fn save_window_size(&self) -> Result<(), glib::BoolError> {
+            let obj = self.obj();
+            let (width, height) = obj.default_size();
+            self.settings.set_int("window-width", width)?;
+            self.settings.set_int("window-height", height)?;
+            self.settings
+                .set_boolean("window-maximized", obj.is_maximized())?;
+            Ok(())
+        }
+
+        /// Starts a new load of the usage, superseding the ones in progress.
+        fn next_load_generation(&self) -> u64 {
+            let generation = self.load_generation.get() + 1;
+            self.load_generation.set(generation);
+            generation
+        }
+
+        fn duration_format(&self) -> DurationFormat {
+            DurationFormat::from_nick(&self.settings.string("duration-format"))
+        }
+
+        /// Shows the durations in the `duration-format` setting without
+        /// loading the usage again.
+        fn update_duration_format(&self) {
+            self.set_chart_duration_format();
+            self.update_heatmap();
+            self.update_usage_views();
+            self.update_details();
+            self.update_hidden_label();
+
+            let app = self.details_app.borrow().clone();
+            let history = self.details_history.borrow().clone();
+            if let (Some(app), Some((days, total))) = (app, history) {
+                self.show_details_history(&app, &days, total);
+            }
+        }
+
+        /// Sets the duration format of the chart tooltips, except the
+        /// heatmap's which `update_heatmap` sets.
+        fn set_chart_duration_format(&self) {
+            let duration_format = self.duration_format();
+            self.timeline.set_duration_format(duration_format);
+            self.bar_chart.set_duration_format(duration_format);
+            self.details_bar_chart.set_duration_format(duration_format);
+        }
+
+        fn update_hidden_label(&self) {
+            let hidden = self.hidden_usage.get();
+            self.hidden_label.set_label(&gettext!(
+                "{} hidden",
+                self.duration_format().format(hidden)
+            ));
+            self.hidden_bar.set_revealed(!hidden.is_zero());
+        }
+
+        /// Moves the selected range by its own length, `direction` times.
+        fn shift_range(&self, direction: i64) {
+            let (start, end) = self.selected_range();
+            let length = (end - start).num_days() + 1;
+            let offset = chrono::Duration::days(length * direction);
+            self.set_date_range(start + offset, end + offset);
+        }
+
+        /// Shows the preset matching the selected range, or "Custom".
+        fn sync_preset(&self) {
+            let range = self.selected_range();
+            let today = Local::now().date_naive();
+            let preset = DatePreset::ALL
+                .into_iter()
+                .find(|preset| preset.range(today) == Some(range))
+                .unwrap_or(DatePreset::Custom);
+
+            if self.preset_dropdown.selected() != preset as u32 {
+                self.updating_range.set(true);
+                self.preset_dropdown.set_selected(preset as u32);
+                self.updating_range.set(false);
+            }
+        }
+
+        pub(super) fn export(&self) {
+            let Some((start, end)) = self.loaded_range.get() else {
+                return;
+            };
+
+            let filters = gio::ListStore::new::<gtk::FileFilter>();
+            let csv_filter = gtk::FileFilter::new();
+            csv_filter.set_name(Some(&gettext("CSV")));
+            csv_filter.add_suffix("csv");
+            filters.append(&csv_filter);
+            let json_filter = gtk::FileFilter::new();
+            json_filter.set_name(Some(&gettext("JSON")));
+            json_filter.add_suffix("json");
+            filters.append(&json_filter);
+
+            let dialog = gtk::FileDialog::builder()
+                .title(gettext("Export Usage"))
+                .initial_name(format!("app-usage-{start}-{end}.csv"))
+                .filters(&filters)
+                .modal(true)
+                .build();
+
+            dialog.save(
+                Some(&*self.obj()),
+                gio::Cancellable::NONE,
+                glib::clone!(@weak self as this => move |result| {
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    let format = ExportFormat::from_path(&path);
+                    let contents = export::export(format, start, end, &this.apps_usage.borrow());
+                    if let Err(err) = std::fs::write(&path, contents) {
+                        let toast = adw::Toast::new(&gettext!("Could not export the usage: {}", err));
+                        this.toast_overlay.add_toast(toast);
+                    }
+                }),
+            );
+        }
+
+        pub(super) fn save_chart(&self) {
+            let Some((start, end)) = self.loaded_range.get() else {
+                return;
+            };
+
+            let filters = gio::ListStore::new::<gtk::FileFilter>();
+            for (name, suffix) in [
+                (gettext("PNG Image"), "png"),
+                (gettext("SVG Image"), "svg"),
+                (gettext("PDF Document"), "pdf"),
+            ] {
+                let filter = gtk::FileFilter::new();
+                filter.set_name(Some(&name));
+                filter.add_suffix(suffix);
+                filters.append(&filter);
+            }
+
+            let dialog = gtk::FileDialog::builder()
+                .title(gettext("Save Chart"))
+                .initial_name(format!("app-usage-{start}-{end}.png"))
+                .filters(&filters)
+                .modal(true)
+                .build();
+
+            dialog.save(
+                Some(&*self.obj()),
+                gio::Cancellable::NONE,
+                glib::clone!(@weak self as this => move |result| {
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    let heading = if start == end {
+                        gettext!("App Usage on {}", start.format("%a %d %b %Y"))
+                    } else {
+                        gettext!(
+                            "App Usage from {} to {}",
+                            start.format("%a %d %b %Y"),
+                            end.format("%a %d %b %Y")
+                        )
+                    };
+                    let format = ChartFormat::from_path(&path);
+                    let duration_format = this.duration_format();
+                    let result = export::save_chart(
+                        format,
+                        &path,
+                        CHART_WIDTH,
+                        CHART_HEIGHT,
+                        |context| {
+                            this.pie_chart.render(
+                                context,
+                                CHART_WIDTH,
+                                CHART_HEIGHT,
+                                &heading,
+                                duration_format,
+                            )
+                        },
+                    );
+                    if let Err(err) = result {
+                        let toast = adw::Toast::new(&gettext!("Could not save the chart: {}", err));
+                        this.toast_overlay.add_toast(toast);
                     }
+                }),
+            );
+        }
+
+        fn setup_column_view(&self) {
+            let tree_model =
+                gtk::TreeListModel::new(self.usage_store.clone(), false, false, |item| {
+                    let object = item.downcast_ref::<AppUsageObject>()?;
+                    object.children().map(|children| children.upcast())
+                });
+
+            self.search_filter.set_filter_func(
+                glib::clone!(@weak self as this => @default-return true, move |item| {
+                    this.row_matches_search(item)
+                }),
+            );
+            let filter_model =
+                gtk::FilterListModel::new(Some(tree_model), Some(self.search_filter.clone()));
+
+            self.sort_mode_sorter.set_sort_func(
+                glib::clone!(@weak self as this => @default-return gtk::Ordering::Equal, move |a, b| {
+                    let (Some(a), Some(b)) = (
+                        a.downcast_ref::<AppUsageObject>(),
+                        b.downcast_ref::<AppUsageObject>(),
+                    ) else {
+                        return gtk::Ordering::Equal;
+                    };
+                    let (a_name, b_name) = (a.display_name(), b.display_name());
+                    this.sort_mode
+                        .get()
+                        .compare(
+                            (&a_name, a.duration(), a.previous_duration()),
+                            (&b_name, b.duration(), b.previous_duration()),
+                        )
+                        .into()
+                }),
+            );
+            let sorter = gtk::MultiSorter::new();
+            if let Some(column_sorter) = self.column_view.sorter() {
+                sorter.append(column_sorter);
+            }
+            sorter.append(self.sort_mode_sorter.clone());
+            // keeps the apps of a category under it
+            let row_sorter = gtk::TreeListRowSorter::new(Some(sorter));
+            let sort_model = gtk::SortListModel::new(Some(filter_model), Some(row_sorter));
+
+            self.usage_selection.set_autoselect(false);
+            self.usage_selection.set_can_unselect(true);
+            self.usage_selection.set_model(Some(&sort_model));
+            self.usage_selection.connect_selected_item_notify(
+                glib::clone!(@weak self as this => move |selection| {
+                    let title = row_object(selection.selected_item()).map(|object| object.title());
+                    this.pie_chart.set_selected_title(title);
+                    this.update_heatmap();
+                }),
+            );
+            self.column_view.set_model(Some(&self.usage_selection));
+            self.column_view.connect_activate(
+                glib::clone!(@weak self as this => move |_, position| {
+                    this.on_row_activated(position);
+                }),
+            );
+
+            let app_column = gtk::ColumnViewColumn::builder()
+                .title(gettext("App"))
+                .factory(&self.app_cell_factory())
+                .sorter(&gtk::StringSorter::new(Some(object_property(
+                    "display-name",
+                ))))
+                .expand(true)
+                .build();
+            let duration_column = gtk::ColumnViewColumn::builder()
+                .title(gettext("Time"))
+                .factory(&self.duration_cell_factory())
+                .sorter(&gtk::NumericSorter::new(Some(object_property("seconds"))))
+                .build();
+            let percentage_column = gtk::ColumnViewColumn::builder()
+                .title(gettext("Share"))
+                .factory(&self.label_cell_factory("percentage-label"))
+                .sorter(&gtk::NumericSorter::new(Some(object_property(
+                    "percentage",
+                ))))
+                .build();
+            let average_column = gtk::ColumnViewColumn::builder()
+                .title(gettext("Daily Average"))
+                .factory(&self.label_cell_factory("daily-average-label"))
+                .sorter(&gtk::NumericSorter::new(Some(object_property(
+                    "daily-average-seconds",
+                ))))
+                .build();
+            let change_column = gtk::ColumnViewColumn::builder()
+                .title(gettext("Change"))
+                .factory(&self.label_cell_factory("change-label"))
+                .sorter(&gtk::CustomSorter::new(|a, b| {
+                    let (Some(a), Some(b)) = (
+                        a.downcast_ref::<AppUsageObject>(),
+                        b.downcast_ref::<AppUsageObject>(),
+                    ) else {
+                        return gtk::Ordering::Equal;
+                    };
+                    let (a_name, b_name) = (a.display_name(), b.display_name());
+                    SortMode::Change
+                        .compare(
+                            (&a_name, a.duration(), a.previous_duration()),
+                            (&b_name, b.duration(), b.previous_duration()),
+                        )
+                        .into()
+                }))
+                .build();
+            self.settings
+                .bind("compare-previous-period", &change_column, "visible")
+                .get_only()
+                .build();
+            for column in [
+                &app_column,
+                &duration_column,
+                &change_column,
+                &percentage_column,
+                &average_column,
+            ] {
+                self.column_view.append_column(column);
+            }
+        }
+
+        /// Cells with the icon and name of an app, or the name of a category
+        /// with an expander for its apps.
+        fn app_cell_factory(&self) -> gtk::SignalListItemFactory {
+            let factory = gtk::SignalListItemFactory::new();
+            factory.connect_setup(glib::clone!(@weak self as this => move |_, list_item| {
+                let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() else {
+                    return;
+                };
+                let icon = gtk::Image::builder().pixel_size(24).build();
+                let name = gtk::Label::builder()
+                    .xalign(0.0)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build();
+                name.add_css_class("heading");
+                let container = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+                container.append(&icon);
+                container.append(&name);
+                let expander = gtk::TreeExpander::builder().child(&container).build();
+
+                list_item
+                    .property_expression("item")
+                    .bind(&expander, "list-row", gtk::Widget::NONE);
+                let object = row_object_expression(list_item);
+                let icon_expression = object.chain_property::<AppUsageObject>("icon");
+                icon_expression.bind(&icon, "gicon", gtk::Widget::NONE);
+                icon_expression
+                    .chain_closure::<bool>(glib::closure!(
+                        |_: Option<glib::Object>, icon: Option<gio::Icon>| icon.is_some()
+                    ))
+                    .bind(&icon, "visible", gtk::Widget::NONE);
+                object
+                    .chain_property::<AppUsageObject>("display-name")
+                    .bind(&name, "label", gtk::Widget::NONE);
+                object
+                    .chain_property::<AppUsageObject>("title")
+                    .bind(&expander, "tooltip-text", gtk::Widget::NONE);
+
+                this.add_row_menu_gesture(expander.upcast_ref(), list_item);
+                list_item.set_child(Some(&expander));
+            }));
+            factory
+        }
+
+        /// Cells with the duration, and progress towards the limit or goal
+        /// when set.
+        fn duration_cell_factory(&self) -> gtk::SignalListItemFactory {
+            let factory = gtk::SignalListItemFactory::new();
+            factory.connect_setup(glib::clone!(@weak self as this => move |_, list_item| {
+                let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() else {
+                    return;
+                };
+                let duration = gtk::Label::builder().xalign(1.0).build();
+                // uniform, the default offsets color a full bar as good, which
+                // is wrong for limits
+                let level_bar = gtk::LevelBar::for_interval(0.0, 1.0);
+                level_bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_LOW));
+                level_bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_HIGH));
+                level_bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_FULL));
+                let container = gtk::Box::builder()
+                    .orientation(gtk::Orientation::Vertical)
+                    .spacing(6)
+                    .valign(gtk::Align::Center)
+                    .build();
+                container.append(&duration);
+                container.append(&level_bar);
 
-                    let mut total = 0.0;
-
-                    for app_usage in apps_usage {
-                        // add apps to listbox
-
-                        let row = gtk::ListBoxRow::new();
-                        let container = gtk::Box::new(gtk::Orientation::Horizontal, 20);
-                        let title = gtk::Label::new(Some(&app_usage.app));
-                        title.add_css_class("heading");
-                        let duration = Duration::from_secs(app_usage.duration.as_secs());
-                        let duration = gtk::Label::new(Some(
-                            &humantime::format_duration(duration).to_string(),
-                        ));
-                        duration.set_halign(gtk::Align::End);
-                        duration.set_hexpand(true);
-                        container.append(&title);
-                        container.append(&duration);
-                        row.set_child(Some(&container));
-                        self.listbox.append(&row);
-
-                        let seconds = app_usage.duration.as_secs_f64();
-
-                        // add apps to pie chart
-                        self.pie_chart
-                            .add_item(&PieChartItem::new(&app_usage.app, seconds));
-
-                        total += seconds;
+                let object = row_object_expression(list_item);
+                object
+                    .chain_property::<AppUsageObject>("duration-label")
+                    .bind(&duration, "label", gtk::Widget::NONE);
+                gtk::ClosureExpression::new::<Vec<String>>(
+                    [
+                        object.chain_property::<AppUsageObject>("exceeded"),
+                        object.chain_property::<AppUsageObject>("goal-met"),
+                    ],
+                    glib::closure!(|_: Option<glib::Object>, exceeded: bool, goal_met: bool| {
+                        goal_css_classes(exceeded, goal_met)
+                    }),
+                )
+                .bind(&duration, "css-classes", gtk::Widget::NONE);
+                object
+                    .chain_property::<AppUsageObject>("goal-progress")
+                    .bind(&level_bar, "value", gtk::Widget::NONE);
+                object
+                    .chain_property::<AppUsageObject>("exceeded")
+                    .chain_closure::<Vec<String>>(glib::closure!(
+                        |_: Option<glib::Object>, exceeded: bool| goal_css_classes(exceeded, false)
+                    ))
+                    .bind(&level_bar, "css-classes", gtk::Widget::NONE);
+                let goal_tooltip = object.chain_property::<AppUsageObject>("goal-tooltip");
+                goal_tooltip.bind(&level_bar, "tooltip-text", gtk::Widget::NONE);
+                goal_tooltip
+                    .chain_closure::<bool>(glib::closure!(
+                        |_: Option<glib::Object>, tooltip: Option<String>| tooltip.is_some()
+                    ))
+                    .bind(&level_bar, "visible", gtk::Widget::NONE);
+
+                this.add_row_menu_gesture(container.upcast_ref(), list_item);
+                list_item.set_child(Some(&container));
+            }));
+            factory
+        }
+
+        /// Cells with a label showing the text `property` of the row.
+        fn label_cell_factory(&self, property: &'static str) -> gtk::SignalListItemFactory {
+            let factory = gtk::SignalListItemFactory::new();
+            factory.connect_setup(glib::clone!(@weak self as this => move |_, list_item| {
+                let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() else {
+                    return;
+                };
+                let label = gtk::Label::builder().xalign(1.0).build();
+                label.add_css_class("dim-label");
+                row_object_expression(list_item)
+                    .chain_property::<AppUsageObject>(property)
+                    .bind(&label, "label", gtk::Widget::NONE);
+
+                this.add_row_menu_gesture(label.upcast_ref(), list_item);
+                list_item.set_child(Some(&label));
+            }));
+            factory
+        }
+
+        /// Pops up the menu of the row of `list_item` on right click on `widget`.
+        fn add_row_menu_gesture(&self, widget: &gtk::Widget, list_item: &gtk::ListItem) {
+            let gesture = gtk::GestureClick::builder()
+                .button(gdk::BUTTON_SECONDARY)
+                .build();
+            gesture.connect_pressed(
+                glib::clone!(@weak self as this, @weak widget, @weak list_item => move |_, _, x, y| {
+                    if let Some(object) = row_object(list_item.item()) {
+                        this.show_row_menu(&object, &widget, x, y);
                     }
+                }),
+            );
+            widget.add_controller(gesture);
+        }
+
+        /// Expands or collapses a category, or shows the details of an app.
+        fn on_row_activated(&self, position: u32) {
+            let Some(row) = self
+                .usage_selection
+                .item(position)
+                .and_downcast::<gtk::TreeListRow>()
+            else {
+                return;
+            };
+            let Some(object) = row.item().and_downcast::<AppUsageObject>() else {
+                return;
+            };
+            if object.is_category() {
+                row.set_expanded(!row.is_expanded());
+            } else {
+                self.show_details(&object.title());
+            }
+        }
+
+        /// Selects the row of the app or category `title`, returning it if
+        /// found, and scrolls to it and focuses it if `focus` is set.
+        pub(super) fn select_row_by_title(
+            &self,
+            title: &str,
+            focus: bool,
+        ) -> Option<gtk::TreeListRow> {
+            let position = (0..self.usage_selection.n_items()).find(|position| {
+                row_object(self.usage_selection.item(*position))
+                    .is_some_and(|object| object.title() == title)
+            })?;
+            self.usage_selection.set_selected(position);
+            if focus {
+                self.column_view
+                    .scroll_to(position, None, gtk::ListScrollFlags::FOCUS, None);
+            }
+            self.usage_selection.item(position).and_downcast()
+        }
+
+        /// Fills the details page with the usage of `app` and shows it.
+        pub(super) fn show_details(&self, app: &str) {
+            self.details_app.replace(Some(app.to_string()));
+            self.update_details();
+            self.load_details_history(app);
+
+            let visible_tag = self
+                .navigation_view
+                .visible_page()
+                .and_then(|page| page.tag());
+            if visible_tag.as_deref() != Some("details") {
+                self.navigation_view.push_by_tag("details");
+            }
+        }
+
+        fn update_details(&self) {
+            let Some(app) = self.details_app.borrow().clone() else {
+                return;
+            };
+
+            let apps_usage = self.apps_usage.borrow();
+            let total: f64 = apps_usage
+                .iter()
+                .map(|app_usage| app_usage.duration.as_secs_f64())
+                .sum();
+            let duration = apps_usage
+                .iter()
+                .find(|app_usage| app_usage.app == app)
+                .map(|app_usage| app_usage.duration)
+                .unwrap_or_default();
+            let share = if total > 0.0 {
+                duration.as_secs_f64() / total * 100.0
+            } else {
+                0.0
+            };
+
+            self.details_page
+                .set_title(&app_info::display_info(&app).name);
+            self.details_duration_row
+                .set_subtitle(&self.duration_format().format(duration));
+            self.details_share_row.set_subtitle(&format!("{share:.1}%"));
+        }
+
+        fn load_details_history(&self, app: &str) {
+            for row in [
+                &self.details_history_total_row,
+                &self.details_average_row,
+                &self.details_longest_day_row,
+                &self.details_history_share_row,
+            ] {
+                row.set_subtitle("…");
+            }
+            self.details_bar_chart.set_bars(Vec::new());
+            self.details_history.replace(None);
+
+            let Some(application) = self.application() else {
+                return;
+            };
+            let db = application.db();
+            let sender = self.sender.clone();
+            let app = app.to_string();
+            let filter = UsageFilter::from_settings(&self.settings);
+            let today = Local::now().date_naive();
+            application.spawn(async move {
+                let msg = match fetch_app_history(&db, &app, today, &filter).await {
+                    Ok((days, total)) => Message::AppHistory(app, days, total),
+                    Err(err) => Message::HistoryFailed(app, err),
+                };
+                sender.send(msg).expect("failed to send app history");
+            });
+        }
+
+        /// Shows the daily usage of `app` over the last days, next to `total`,
+        /// the usage of every app over those same days.
+        fn show_details_history(&self, app: &str, days: &[(NaiveDate, Duration)], total: Duration) {
+            // the user may have moved on to another app while this loaded
+            if self.details_app.borrow().as_deref() != Some(app) {
+                return;
+            }
+            self.details_history.replace(Some((days.to_vec(), total)));
+
+            let color_index = self
+                .apps_usage
+                .borrow()
+                .iter()
+                .position(|app_usage| app_usage.app == app)
+                .unwrap_or(0);
+            let bars = days
+                .iter()
+                .map(|(day, duration)| BarChartBar {
+                    title: day.format("%d").to_string(),
+                    segments: vec![BarChartSegment {
+                        title: app.to_string(),
+                        weight: duration.as_secs_f64(),
+                        color_index,
+                    }],
+                })
+                .collect();
+            self.details_bar_chart.set_bars(bars);
+
+            let duration_format = self.duration_format();
+            let app_total: Duration = days.iter().map(|(_, duration)| *duration).sum();
+            let average = app_total / days.len().max(1) as u32;
+            let share = if total.is_zero() {
+                0.0
+            } else {
+                app_total.as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            self.details_history_total_row
+                .set_subtitle(&duration_format.format(app_total));
+            self.details_average_row
+                .set_subtitle(&duration_format.format(average));
+            self.details_history_share_row
+                .set_subtitle(&format!("{share:.1}%"));
+
+            // the first of the longest days
+            let longest_day = days
+                .iter()
+                .rev()
+                .max_by_key(|(_, duration)| *duration)
+                .filter(|(_, duration)| !duration.is_zero());
+            match longest_day {
+                Some((day, duration)) => self.details_longest_day_row.set_subtitle(&format!(
+                    "{} ({})",
+                    duration_format.format(*duration),
+                    day.format("%a %d %b")
+                )),
+                None => self.details_longest_day_row.set_subtitle(&gettext("None")),
+            }
+        }
+
+        /// Describes the target of `goal` over `days` days.
+        fn goal_tooltip(&self, goal: AppGoal, days: u32) -> String {
+            let target = self.duration_format().format(goal.target(days));
+            match goal.kind {
+                AppGoalKind::Limit => gettext!("Limit: {}", target),
+                AppGoalKind::Goal => gettext!("Goal: {}", target),
+            }
+        }
+
+        /// The application owning the runtime and database, unless the window
+        /// was already closed.
+        fn application(&self) -> Option<HyprlandAppTimerGuiApplication> {
+            self.obj().application().and_downcast()
+        }
+
+        /// Marks the days with usage in the month shown by each calendar,
+        /// unless already marked.
+        fn mark_days_with_usage(&self) {
+            let Some(application) = self.application() else {
+                return;
+            };
+            let mut fetched_month = None;
+            for (calendar, marked_month) in [
+                (&*self.calendar_date_start, &self.start_marked_month),
+                (&*self.calendar_date_end, &self.end_marked_month),
+            ] {
+                let date = calendar.date();
+                let month = (date.year(), date.month());
+                if marked_month.get() == Some(month) {
+                    continue;
+                }
+                calendar.clear_marks();
+
+                // the days found are marked in every calendar showing the month
+                if fetched_month != Some(month) {
+                    let db = application.db();
+                    let sender = self.sender.clone();
+                    let filter = UsageFilter::from_settings(&self.settings);
+                    let today = Local::now().date_naive();
+                    let first_day = NaiveDate::from_ymd_opt(month.0, month.1 as u32, 1)
+                        .expect("invalid calendar month");
+                    application.spawn(async move {
+                        match fetch_days_with_usage(&db, first_day, today, &filter).await {
+                            Ok(days) => sender
+                                .send(Message::DaysWithUsage(first_day, days))
+                                .expect("failed to send days with usage"),
+                            Err(err) => {
+                                eprintln!("Error: failed to find the days with usage: {err}")
+                            }
+                        }
+                    });
+                    fetched_month = Some(month);
+                }
+            }
+        }
+
+        fn load_heatmap(&self) {
+            let Some(application) = self.application() else {
+                return;
+            };
+            let db = application.db();
+            let sender = self.sender.clone();
+            let today = Local::now().date_naive();
+            application.spawn(async move {
+                match fetch_heatmap_days(db, today).await {
+                    Ok(days) => sender
+                        .send(Message::HeatmapDays(days))
+                        .expect("failed to send heatmap days"),
+                    Err(err) => eprintln!("Error: failed to load the calendar heatmap: {err}"),
+                }
+            });
+        }
+
+        /// Shades the heatmap by the daily usage of the app selected in the
+        /// list, or of every app.
+        fn update_heatmap(&self) {
+            let app = row_object(self.usage_selection.selected_item())
+                .filter(|object| !object.is_category());
+            let filter = UsageFilter::from_settings(&self.settings);
+            let days = self
+                .heatmap_days
+                .borrow()
+                .iter()
+                .map(|(day, day_usage)| {
+                    let duration = filter
+                        .apply(day_usage.clone())
+                        .iter()
+                        .filter(|app_usage| {
+                            app.as_ref()
+                                .map_or(true, |app| app.title() == app_usage.app)
+                        })
+                        .map(|app_usage| app_usage.duration)
+                        .sum();
+                    (*day, duration)
+                })
+                .collect();
+            self.heatmap.set_duration_format(self.duration_format());
+            self.heatmap.set_days(days);
+            match app {
+                Some(app) => self.heatmap_title.set_label(&app.display_name()),
+                None => self.heatmap_title.set_label(&gettext("All Apps")),
+            }
+        }
+
+        /// Updates the usage list and pie chart from the loaded usage, by
+        /// app or by category.
+        fn update_usage_views(&self) {
+            let selected_title = self.pie_chart.selected_title();
+            let Some((start, end)) = self.loaded_range.get() else {
+                self.usage_store.remove_all();
+                return;
+            };
+            let apps_usage = self.apps_usage.borrow();
+            let previous_usage = self.previous_usage.borrow();
+            let goals = app_goal::load(&self.settings);
+            let days = ((end - start).num_days() + 1) as u32;
+            let duration_format = self.duration_format();
+            let total: Duration = apps_usage.iter().map(|app_usage| app_usage.duration).sum();
+            let is_exceeded = |app_usage: &AppUsage| {
+                goals
+                    .get(&app_usage.app)
+                    .is_some_and(|goal| goal.is_exceeded(app_usage.duration, days))
+            };
+            let app_object = |app_usage: &AppUsage| {
+                let display_info = app_info::display_info(&app_usage.app);
+                let previous = previous_usage
+                    .iter()
+                    .find(|previous| previous.app == app_usage.app)
+                    .map(|previous| previous.duration)
+                    .unwrap_or_default();
+                let object = self.usage_object(
+                    &app_usage.app,
+                    &display_info.name,
+                    app_usage.duration,
+                    previous,
+                    total,
+                    days,
+                );
+                object.set_icon(Some(app_icon(&display_info)));
+                if let Some(goal) = goals.get(&app_usage.app).copied() {
+                    object.set_goal_progress(goal.progress(app_usage.duration, days).min(1.0));
+                    object.set_goal_tooltip(Some(self.goal_tooltip(goal, days)));
+                    object.set_exceeded(goal.is_exceeded(app_usage.duration, days));
+                    object.set_goal_met(goal.is_met(app_usage.duration, days));
+                }
+                object
+            };
+
+            // the pie chart keeps the longest first whatever the list's order
+            let mut items = Vec::new();
+            let mut objects = Vec::new();
+
+            if self.is_grouped_by_category() {
+                let rules = category::load(&self.settings);
+                let uncategorized = gettext("Uncategorized");
+                let previous_categories = category::group(&rules, &previous_usage, &uncategorized);
+                let categories = category::group(&rules, &apps_usage, &uncategorized);
+
+                for category_usage in &categories {
+                    let exceeded = category_usage.apps.iter().any(|app| is_exceeded(*app));
+                    let item = PieChartItem::new(
+                        &category_usage.category,
+                        category_usage.duration.as_secs_f64(),
+                    );
+                    item.set_exceeded(exceeded);
+                    items.push(item);
 
-                    self.pie_chart.set_title(
-                        humantime::format_duration(Duration::from_secs_f64(total.round()))
-                            .to_string(),
+                    let previous = previous_categories
+                        .iter()
+                        .find(|previous| previous.category == category_usage.category)
+                        .map(|previous| previous.duration)
+                        .unwrap_or_default();
+                    let apps: Vec<AppUsageObject> = category_usage
+                        .apps
+                        .iter()
+                        .map(|app_usage| app_object(*app_usage))
+                        .collect();
+                    let object = AppUsageObject::new_category(
+                        &category_usage.category,
+                        category_usage.duration,
+                        &apps,
                     );
+                    self.fill_usage_object(&object, previous, total, days);
+                    object.set_exceeded(exceeded);
+                    objects.push(object);
+                }
+            } else {
+                for app_usage in apps_usage.iter() {
+                    let object = app_object(app_usage);
+                    let item = PieChartItem::new(&app_usage.app, app_usage.duration.as_secs_f64());
+                    item.set_display_name(Some(object.display_name()));
+                    item.set_exceeded(object.exceeded());
+                    items.push(item);
+                    objects.push(object);
+                }
+            }
+
+            drop(apps_usage);
+            drop(previous_usage);
+            app_usage_object::sync_store(&self.usage_store, &objects);
+            self.expand_search_matches();
+            self.pie_chart.set_items(&items);
+            self.pie_chart.set_title(duration_format.format(total));
+            let previous_total: Duration = self
+                .previous_usage
+                .borrow()
+                .iter()
+                .map(|app_usage| app_usage.duration)
+                .sum();
+            self.pie_chart.set_subtitle(
+                self.settings
+                    .boolean("compare-previous-period")
+                    .then(|| duration_format.format_change(total, previous_total)),
+            );
+
+            if let Some(title) = selected_title {
+                self.select_row_by_title(&title, false);
+            }
+        }
+
+        /// Object listing the usage of the app or category `title`.
+        fn usage_object(
+            &self,
+            title: &str,
+            display_name: &str,
+            duration: Duration,
+            previous: Duration,
+            total: Duration,
+            days: u32,
+        ) -> AppUsageObject {
+            let object = AppUsageObject::new(title, display_name, duration);
+            self.fill_usage_object(&object, previous, total, days);
+            object
+        }
+
+        /// Sets the figures of `object` derived from its duration.
+        fn fill_usage_object(
+            &self,
+            object: &AppUsageObject,
+            previous: Duration,
+            total: Duration,
+            days: u32,
+        ) {
+            let duration_format = self.duration_format();
+            let duration = object.duration();
+            let percentage = if total.is_zero() {
+                0.0
+            } else {
+                duration.as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            let daily_average = duration / days.max(1);
+            object.set_previous_seconds(previous.as_secs_f64());
+            object.set_percentage(percentage);
+            object.set_percentage_label(format!("{percentage:.1}%"));
+            object.set_daily_average_seconds(daily_average.as_secs_f64());
+            object.set_duration_label(duration_format.format(duration));
+            object.set_daily_average_label(duration_format.format(daily_average));
+            object.set_change_label(duration_format.format_change(duration, previous));
+        }
+
+        /// Whether the row `item` of the column view matches the text in the
+        /// search entry.
+        fn row_matches_search(&self, item: &glib::Object) -> bool {
+            let search_text = self.search_entry.text();
+            if search_text.is_empty() {
+                return true;
+            }
+            let Some(row) = item.downcast_ref::<gtk::TreeListRow>() else {
+                return true;
+            };
+            let Some(object) = row.item().and_downcast::<AppUsageObject>() else {
+                return true;
+            };
+            // categories with a matching app are kept, see
+            // `expand_search_matches`, and the apps of a matching category
+            // stay listed under it
+            object.matches(&search_text)
+                || row
+                    .parent()
+                    .and_then(|parent| parent.item().and_downcast::<AppUsageObject>())
+                    .is_some_and(|category| {
+                        category
+                            .title()
+                            .to_lowercase()
+                            .contains(&search_text.to_lowercase())
+                    })
+        }
+
+        /// Expands the categories listed only because some of their apps
+        /// match the search, as the rows of those apps don't exist until
+        /// then.
+        fn expand_search_matches(&self) {
+            let search_text = self.search_entry.text();
+            if search_text.is_empty() {
+                return;
+            }
+
+            let rows: Vec<gtk::TreeListRow> = (0..self.usage_selection.n_items())
+                .filter_map(|position| self.usage_selection.item(position).and_downcast())
+                .collect();
+            for row in rows {
+                let matching_child = row
+                    .item()
+                    .and_downcast::<AppUsageObject>()
+                    .and_then(|object| object.children())
+                    .is_some_and(|children| {
+                        children
+                            .iter::<AppUsageObject>()
+                            .flatten()
+                            .any(|child| child.matches(&search_text))
+                    });
+                if matching_child && !row.is_expanded() {
+                    row.set_expanded(true);
+                }
+            }
+        }
+
+        /// Pops up the menu of the app `object` at `x`, `y` of `widget`.
+        fn show_row_menu(&self, object: &AppUsageObject, widget: &gtk::Widget, x: f64, y: f64) {
+            if object.is_category() {
+                return;
+            }
+            let app = object.title();
+
+            // hidden apps are only listed while shown
+            let (label, action) = if UsageFilter::from_settings(&self.settings).is_hidden(&app) {
+                (gettext("Stop Hiding This App"), "win.unhide-app")
+            } else {
+                (gettext("Hide This App"), "win.hide-app")
+            };
+            let item = gio::MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(Some(action), Some(&app.to_variant()));
+            let menu = gio::Menu::new();
+            menu.append_item(&item);
+
+            let popover = gtk::PopoverMenu::from_model(Some(&menu));
+            popover.set_parent(widget);
+            popover.set_has_arrow(false);
+            popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+            popover.connect_closed(|popover| {
+                // unparent once the menu item's action has been activated
+                glib::idle_add_local_once(glib::clone!(@weak popover => move || {
+                    popover.unparent();
+                }));
+            });
+            popover.popup();
+        }
+
+        /// Adds `app` to the excluded apps, or removes it.
+        pub(super) fn set_app_hidden(&self, app: &str, hidden: bool) {
+            let mut excluded_apps: Vec<String> = self
+                .settings
+                .strv("excluded-apps")
+                .iter()
+                .map(|excluded| excluded.to_string())
+                .filter(|excluded| excluded != app)
+                .collect();
+            if hidden {
+                excluded_apps.push(app.to_string());
+            }
+            self.settings
+                .set_strv("excluded-apps", excluded_apps)
+                .expect("failed to set excluded apps");
+        }
+
+        fn is_grouped_by_category(&self) -> bool {
+            self.settings.boolean("group-by-category")
+        }
+
+        fn handle_message(&self, msg: Message) {
+            match msg {
+                Message::AppsUsage {
+                    generation,
+                    start,
+                    end,
+                    apps_usage,
+                    previous_usage,
+                    hidden,
+                } => {
+                    if generation != self.load_generation.get() {
+                        return;
+                    }
+                    // with only hidden usage, the list still offers to show it
+                    if apps_usage.is_empty() && hidden.is_zero() {
+                        self.main_stack.set_visible_child_name("empty");
+                    } else {
+                        self.main_stack.set_visible_child_name("content");
+                    }
+                    // a refresh of the same range keeps the chart's state
+                    if self.loaded_range.get() != Some((start, end)) {
+                        self.pie_chart.clear();
+                    }
+                    self.loaded_range.set(Some((start, end)));
+                    // keeps the heatmap up to date with refreshes of a day,
+                    // filtered the same way as the rest of it is shown
+                    if start == end {
+                        if let Some((_, day_usage)) = self
+                            .heatmap_days
+                            .borrow_mut()
+                            .iter_mut()
+                            .find(|(day, _)| *day == start)
+                        {
+                            day_usage.clone_from(&apps_usage);
+                        }
+                        self.update_heatmap();
+                    }
+                    *self.apps_usage.borrow_mut() = apps_usage;
+                    *self.previous_usage.borrow_mut() = previous_usage;
+                    self.hidden_usage.set(hidden);
+
+                    self.update_usage_views();
+                    self.update_details();
+                    self.update_hidden_label();
+                }
+                Message::Timeline(generation, lanes) => {
+                    if generation == self.load_generation.get() {
+                        self.timeline.set_lanes(lanes);
+                    }
+                }
+                Message::Days(generation, bars) => {
+                    if generation == self.load_generation.get() {
+                        self.bar_chart.set_bars(bars);
+                    }
+                }
+                Message::DaysWithUsage(first_day, days) => {
+                    for (calendar, marked_month) in [
+                        (&*self.calendar_date_start, &self.start_marked_month),
+                        (&*self.calendar_date_end, &self.end_marked_month),
+                    ] {
+                        // the calendar may have moved to another month since
+                        let date = calendar.date();
+                        if date.year() != first_day.year()
+                            || date.month() as u32 != first_day.month()
+                        {
+                            continue;
+                        }
+                        calendar.clear_marks();
+                        for day in &days {
+                            calendar.mark_day(*day);
+                        }
+                        // recorded only now so a failed fetch is retried
+                        marked_month.set(Some((date.year(), date.month())));
+                    }
+                }
+                Message::LatestDayWithUsage(day) => match day {
+                    Some(day) => self.set_date_range(day, day),
+                    None => {
+                        let toast =
+                            adw::Toast::new(&gettext("No activity recorded in the last year"));
+                        self.toast_overlay.add_toast(toast);
+                    }
+                },
+                Message::HeatmapDays(days) => {
+                    self.heatmap_days.replace(days);
+                    self.update_heatmap();
+                }
+                Message::AppHistory(app, days, total) => {
+                    self.show_details_history(&app, &days, total);
+                }
+                Message::HistoryFailed(app, err) => {
+                    let toast = adw::Toast::new(&gettext!(
+                        "Could not load the history of {}: {}",
+                        app,
+                        err
+                    ));
+                    self.toast_overlay.add_toast(toast);
+                }
+                Message::Error(err) => {
+                    self.error_page.set_description(Some(&err));
+                    self.main_stack.set_visible_child_name("error");
+                }
+                Message::SaveFailed(err) => {
+                    let toast =
+                        adw::Toast::new(&gettext!("Could not save the latest usage: {}", err));
+                    self.toast_overlay.add_toast(toast);
                 }
             }
         }
     }
 
     impl WidgetImpl for HyprlandAppTimerGuiWindow {}
-    impl WindowImpl for HyprlandAppTimerGuiWindow {}
+    impl WindowImpl for HyprlandAppTimerGuiWindow {
+        fn close_request(&self) -> glib::Propagation {
+            if let Err(err) = self.save_window_size() {
+                eprintln!("Error: failed to save window size: {err}");
+            }
+
+            self.parent_close_request()
+        }
+    }
     impl ApplicationWindowImpl for HyprlandAppTimerGuiWindow {}
     impl AdwApplicationWindowImpl for HyprlandAppTimerGuiWindow {}
 
     impl Default for HyprlandAppTimerGuiWindow {
         fn default() -> Self {
-            let rt = tokio::runtime::Builder::new_multi_thread()
-                .worker_threads(1)
-                .enable_all()
-                .build()
-                .expect("failed to buid tokio runtime");
-            let db = rt.block_on(SqliteDB::new()).expect("failed to get client");
             let (sender, receiver) = glib::MainContext::channel(glib::Priority::DEFAULT);
             HyprlandAppTimerGuiWindow {
                 calendar_date_start: Default::default(),
                 calendar_date_end: Default::default(),
-                listbox: Default::default(),
+                column_view: Default::default(),
+                hidden_bar: Default::default(),
+                hidden_label: Default::default(),
+                show_hidden_toggle: Default::default(),
                 date_range_checkbox: Default::default(),
+                date_menu_button: Default::default(),
+                preset_dropdown: Default::default(),
+                updating_range: Default::default(),
+                loaded_range: Default::default(),
+                load_generation: Default::default(),
+                apps_usage: Default::default(),
+                hidden_usage: Default::default(),
+                usage_store: gio::ListStore::new::<AppUsageObject>(),
+                usage_selection: gtk::SingleSelection::new(None::<gio::ListModel>),
+                search_filter: gtk::CustomFilter::new(|_| true),
+                sort_mode_sorter: gtk::CustomSorter::new(|_, _| gtk::Ordering::Equal),
+                sort_mode: Default::default(),
+                previous_usage: Default::default(),
+                search_bar: Default::default(),
+                search_entry: Default::default(),
+                details_app: Default::default(),
+                details_history: Default::default(),
+                category_toggle: Default::default(),
+                navigation_view: Default::default(),
+                details_page: Default::default(),
+                details_duration_row: Default::default(),
+                details_share_row: Default::default(),
+                details_bar_chart: Default::default(),
+                details_history_total_row: Default::default(),
+                details_average_row: Default::default(),
+                details_longest_day_row: Default::default(),
+                details_history_share_row: Default::default(),
+                settings: gio::Settings::new(APP_ID),
                 sender,
                 receiver: RefCell::new(Some(receiver)),
-                rt,
-                db: Arc::new(db),
+                refresh_source: Default::default(),
                 pie_chart: Default::default(),
+                timeline: Default::default(),
+                timeline_page: Default::default(),
+                bar_chart: Default::default(),
+                heatmap_title: Default::default(),
+                heatmap: Default::default(),
+                heatmap_days: Default::default(),
+                start_marked_month: Default::default(),
+                end_marked_month: Default::default(),
+                bar_chart_page: Default::default(),
             }
         }
     }
 
     #[derive(Debug)]
     enum Message {
-        AppsUsage(Vec<AppUsage>),
+        AppsUsage {
+            /// The load these results belong to, like the first field of
+            /// `Timeline` and `Days`.
+            generation: u64,
+            start: NaiveDate,
+            end: NaiveDate,
+            apps_usage: Vec<AppUsage>,
+            /// Usage of the period of the same length just before.
+            previous_usage: Vec<AppUsage>,
+            /// How much of the usage belongs to excluded apps.
+            hidden: Duration,
+        },
+        Timeline(u64, Vec<TimelineLane>),
+        Days(u64, Vec<BarChartBar>),
+        /// Most recent day with usage, if any.
+        LatestDayWithUsage(Option<NaiveDate>),
+        /// Days of the month starting on the given day that have usage.
+        DaysWithUsage(NaiveDate, Vec<u32>),
+        /// Usage of every day of the calendar heatmap, oldest first.
+        HeatmapDays(Vec<(NaiveDate, Vec<AppUsage>)>),
+        /// Loading the usage failed, with the error to show.
+        Error(String),
+        /// The daemon couldn't be asked to save its current usage.
+        SaveFailed(String),
+        /// Daily usage of an app over the last days, and the usage of every
+        /// app over those days.
+        AppHistory(String, Vec<(NaiveDate, Duration)>, Duration),
+        /// The history of an app couldn't be loaded.
+        HistoryFailed(String, String),
+    }
+
+    /// Loads the usage from `start` to `end` (inclusive), sending any error to
+    /// the window instead of panicking.
+    async fn load_apps_usage(
+        db: Arc<OnceCell<SqliteDB>>,
+        sender: Sender<Message>,
+        generation: u64,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: UsageFilter,
+    ) {
+        if let Err(err) = fetch_apps_usage(&db, &sender, generation, start, end, &filter).await {
+            sender
+                .send(Message::Error(err))
+                .expect("failed to send error");
+        }
+    }
+
+    /// Fetches the usage from `start` to `end` (inclusive) and, for a single
+    /// day, its hourly breakdown, or else its daily breakdown.
+    async fn fetch_apps_usage(
+        db: &OnceCell<SqliteDB>,
+        sender: &Sender<Message>,
+        generation: u64,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: &UsageFilter,
+    ) -> Result<(), String> {
+        let db = db
+            .get_or_try_init(SqliteDB::new)
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let apps_usage = db
+            .get_apps_usage(
+                date_naive_to_utc(start),
+                date_naive_to_utc(end.succ_opt().unwrap()),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        let hidden = filter.hidden_duration(&apps_usage);
+        let apps_usage = filter.apply(apps_usage);
+
+        let length = (end - start).num_days() + 1;
+        let previous_usage = filter.apply(
+            db.get_apps_usage(
+                date_naive_to_utc(start - chrono::Duration::days(length)),
+                date_naive_to_utc(start),
+            )
+            .await
+            .map_err(|err| err.to_string())?,
+        );
+
+        // keep the lanes in the same order as the pie chart so colors match
+        let mut lanes: Vec<_> = apps_usage
+            .iter()
+            .map(|app_usage| TimelineLane::new(&app_usage.app))
+            .collect();
+
+        sender
+            .send(Message::AppsUsage {
+                generation,
+                start,
+                end,
+                apps_usage,
+                previous_usage,
+                hidden,
+            })
+            .expect("failed to send apps usage");
+
+        if start != end {
+            let bars = load_daily_usage(db, start, end, &lanes, filter).await?;
+            sender
+                .send(Message::Days(generation, bars))
+                .expect("failed to send daily usage");
+            return Ok(());
+        }
+
+        for hour in 0..24 {
+            let hour_usage = db
+                .get_apps_usage(
+                    usage::local_hour_to_utc(start, hour as u32),
+                    usage::local_hour_to_utc(start, hour as u32 + 1),
+                )
+                .await
+                .map_err(|err| err.to_string())?;
+
+            for app_usage in filter.apply(hour_usage) {
+                let index = match lanes.iter().position(|lane| lane.title == app_usage.app) {
+                    Some(index) => index,
+                    None => {
+                        lanes.push(TimelineLane::new(&app_usage.app));
+                        lanes.len() - 1
+                    }
+                };
+                lanes[index].seconds[hour] += app_usage.duration.as_secs_f64();
+            }
+        }
+
+        sender
+            .send(Message::Timeline(generation, lanes))
+            .expect("failed to send timeline");
+        Ok(())
+    }
+
+    /// Fetches one stacked bar per day, coloring apps by their position in
+    /// `lanes` like the other charts.
+    async fn load_daily_usage(
+        db: &SqliteDB,
+        start: NaiveDate,
+        end: NaiveDate,
+        lanes: &[TimelineLane],
+        filter: &UsageFilter,
+    ) -> Result<Vec<BarChartBar>, String> {
+        let mut bars = Vec::new();
+        for day in start.iter_days().take_while(|day| *day <= end) {
+            let day_usage = usage::fetch_day(db, day).await?;
+
+            let segments = filter
+                .apply(day_usage)
+                .into_iter()
+                .map(|app_usage| BarChartSegment {
+                    color_index: lanes
+                        .iter()
+                        .position(|lane| lane.title == app_usage.app)
+                        .unwrap_or(lanes.len()),
+                    weight: app_usage.duration.as_secs_f64(),
+                    title: app_usage.app,
+                })
+                .collect();
+
+            bars.push(BarChartBar {
+                title: day.format("%a %d").to_string(),
+                segments,
+            });
+        }
+        Ok(bars)
     }
 
-    fn date_glib_to_chrono(date: &glib::DateTime) -> chrono::DateTime<Utc> {
-        let date =
-            NaiveDate::from_ymd_opt(date.year(), date.month() as u32, date.day_of_month() as u32)
-                .unwrap()
-                .and_hms_opt(0, 0, 0)
-                .unwrap();
+    /// Fetches the daily usage of `app` over the [`HISTORY_DAYS`] days ending
+    /// on `today`, along with the total usage of every app over those days.
+    async fn fetch_app_history(
+        db: &OnceCell<SqliteDB>,
+        app: &str,
+        today: NaiveDate,
+        filter: &UsageFilter,
+    ) -> Result<(Vec<(NaiveDate, Duration)>, Duration), String> {
+        let db = db
+            .get_or_try_init(SqliteDB::new)
+            .await
+            .map_err(|err| err.to_string())?;
 
-        Local
-            .from_local_datetime(&date)
+        let start = today - chrono::Duration::days(HISTORY_DAYS - 1);
+        let mut days = Vec::new();
+        let mut total = Duration::ZERO;
+        for day in start.iter_days().take_while(|day| *day <= today) {
+            let day_usage = usage::fetch_day(db, day).await?;
+
+            let mut duration = Duration::ZERO;
+            for app_usage in filter.apply(day_usage) {
+                if app_usage.app == app {
+                    duration += app_usage.duration;
+                }
+                total += app_usage.duration;
+            }
+            days.push((day, duration));
+        }
+        Ok((days, total))
+    }
+
+    /// Looks for the most recent day with usage, going back at most
+    /// [`LATEST_ACTIVITY_DAYS`] days from `today`.
+    async fn fetch_latest_day_with_usage(
+        db: &OnceCell<SqliteDB>,
+        today: NaiveDate,
+        filter: &UsageFilter,
+    ) -> Result<Option<NaiveDate>, String> {
+        let db = db
+            .get_or_try_init(SqliteDB::new)
+            .await
+            .map_err(|err| err.to_string())?;
+
+        for days_ago in 0..LATEST_ACTIVITY_DAYS {
+            let day = today - chrono::Duration::days(days_ago);
+            let day_usage = usage::fetch_day(db, day).await?;
+            if !filter.apply(day_usage).is_empty() {
+                return Ok(Some(day));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Fetches which days of the month starting on `first_day`, up to
+    /// `today`, have usage.
+    async fn fetch_days_with_usage(
+        db: &OnceCell<SqliteDB>,
+        first_day: NaiveDate,
+        today: NaiveDate,
+        filter: &UsageFilter,
+    ) -> Result<Vec<u32>, String> {
+        let db = db
+            .get_or_try_init(SqliteDB::new)
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let mut days = Vec::new();
+        for day in first_day
+            .iter_days()
+            .take_while(|day| day.month() == first_day.month() && *day <= today)
+        {
+            let day_usage = usage::fetch_day(db, day).await?;
+            if !filter.apply(day_usage).is_empty() {
+                days.push(day.day());
+            }
+        }
+        Ok(days)
+    }
+
+    /// Fetches the unfiltered usage of every day of the [`HEATMAP_WEEKS`]
+    /// weeks ending with the one of `today`, up to `today`.
+    async fn fetch_heatmap_days(
+        db: Arc<OnceCell<SqliteDB>>,
+        today: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Vec<AppUsage>)>, String> {
+        db.get_or_try_init(SqliteDB::new)
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let monday = today - chrono::Duration::days(today.weekday().num_days_from_monday().into());
+        let start = monday - chrono::Duration::weeks(HEATMAP_WEEKS - 1);
+        // the database only sums the usage of a range, so each day is its own
+        // query, a few of them running at once instead of one after the other
+        let mut days = Vec::new();
+        let mut tasks = tokio::task::JoinSet::new();
+        for day in start.iter_days().take_while(|day| *day <= today) {
+            if tasks.len() == HEATMAP_CONCURRENT_QUERIES {
+                if let Some(result) = tasks.join_next().await {
+                    days.push(result.map_err(|err| err.to_string())??);
+                }
+            }
+            let db = db.clone();
+            tasks.spawn(async move {
+                let db = db.get().expect("database initialized above");
+                usage::fetch_day(db, day)
+                    .await
+                    .map(|day_usage| (day, day_usage))
+            });
+        }
+
+        while let Some(result) = tasks.join_next().await {
+            days.push(result.map_err(|err| err.to_string())??);
+        }
+        days.sort_by_key(|(day, _)| *day);
+        Ok(days)
+    }
+
+    /// Themed icon of an app, or a generic one.
+    fn app_icon(display_info: &AppDisplayInfo) -> gio::Icon {
+        match &display_info.icon {
+            Some(icon) => icon.clone(),
+            None => gio::ThemedIcon::new("application-x-executable-symbolic").upcast(),
+        }
+    }
+
+    /// Object of a row of the column view.
+    fn row_object(item: Option<glib::Object>) -> Option<AppUsageObject> {
+        item.and_downcast::<gtk::TreeListRow>()?
+            .item()
+            .and_downcast()
+    }
+
+    /// Expression of the object of the row shown by `list_item`.
+    fn row_object_expression(list_item: &gtk::ListItem) -> gtk::PropertyExpression {
+        list_item
+            .property_expression("item")
+            .chain_property::<gtk::TreeListRow>("item")
+    }
+
+    /// Expression of `property` of an `AppUsageObject`, for sorters.
+    fn object_property(property: &str) -> gtk::PropertyExpression {
+        gtk::PropertyExpression::new(
+            AppUsageObject::static_type(),
+            None::<&gtk::Expression>,
+            property,
+        )
+    }
+
+    /// Style classes of a duration over its limit or meeting its goal.
+    fn goal_css_classes(exceeded: bool, goal_met: bool) -> Vec<String> {
+        if exceeded {
+            vec!["warning".to_string()]
+        } else if goal_met {
+            vec!["success".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn date_glib_to_naive(date: &glib::DateTime) -> NaiveDate {
+        NaiveDate::from_ymd_opt(date.year(), date.month() as u32, date.day_of_month() as u32)
             .unwrap()
-            .with_timezone(&Utc)
+    }
+
+    fn date_naive_to_glib(date: NaiveDate) -> glib::DateTime {
+        glib::DateTime::from_local(
+            date.year(),
+            date.month() as i32,
+            date.day() as i32,
+            0,
+            0,
+            0.0,
+        )
+        .expect("invalid date")
     }
 }
 
@@ -251,8 +2060,59 @@ glib::wrapper! {
 
 impl HyprlandAppTimerGuiWindow {
     pub fn new<P: glib::IsA<gtk::Application>>(application: &P) -> Self {
-        glib::Object::builder()
+        let window: Self = glib::Object::builder()
             .property("application", application)
-            .build()
+            .build();
+        window.imp().load_initial();
+        window
+    }
+
+    /// Selects `app` and opens its details page.
+    pub fn show_app(&self, app: &str) {
+        let imp = self.imp();
+        // kept selected once the usage is loaded, for a new window
+        imp.pie_chart.set_selected_title(Some(app.to_string()));
+        imp.select_row_by_title(app, true);
+        imp.show_details(app);
+    }
+
+    fn setup_gactions(&self) {
+        let export_action = gio::ActionEntry::builder("export")
+            .activate(move |window: &Self, _, _| window.imp().export())
+            .build();
+        let save_chart_action = gio::ActionEntry::builder("save-chart")
+            .activate(move |window: &Self, _, _| window.imp().save_chart())
+            .build();
+        let hide_app_action = gio::ActionEntry::builder("hide-app")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(move |window: &Self, _, parameter| {
+                if let Some(app) = parameter.and_then(|parameter| parameter.str()) {
+                    window.imp().set_app_hidden(app, true);
+                }
+            })
+            .build();
+        let unhide_app_action = gio::ActionEntry::builder("unhide-app")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(move |window: &Self, _, parameter| {
+                if let Some(app) = parameter.and_then(|parameter| parameter.str()) {
+                    window.imp().set_app_hidden(app, false);
+                }
+            })
+            .build();
+        let search_action = gio::ActionEntry::builder("search")
+            .activate(move |window: &Self, _, _| {
+                let search_bar = &window.imp().search_bar;
+                search_bar.set_search_mode(!search_bar.is_search_mode());
+            })
+            .build();
+        self.add_action_entries([
+            export_action,
+            save_chart_action,
+            hide_app_action,
+            unhide_app_action,
+            search_action,
+        ]);
+        self.add_action(&self.imp().settings.create_action("sort-mode"));
+        self.add_action(&self.imp().settings.create_action("compare-previous-period"));
     }
 }